@@ -0,0 +1,93 @@
+use crate::lowlevel::types::*;
+
+/// Automatic gain control configuration (AGCCTRL2, AGCCTRL1 and AGCCTRL0).
+///
+/// The carrier sense thresholds in AGCCTRL1 are configured separately, see
+/// [`Cc1101::set_carrier_sense_threshold`](crate::Cc1101::set_carrier_sense_threshold).
+#[derive(Clone, Copy)]
+pub struct AgcConfig {
+    pub max_dvga_gain: MaxDvgaGain,
+    pub max_lna_gain: MaxLnaGain,
+    pub magn_target: MagnTarget,
+    pub lna_priority: AgcLnaPriority,
+    pub hyst_level: HystLevel,
+    pub wait_time: WaitTime,
+    pub freeze: AgcFreeze,
+    pub filter_length: FilterLength,
+}
+
+impl Default for AgcConfig {
+    /// Reset values of the AGC registers.
+    fn default() -> Self {
+        AgcConfig {
+            max_dvga_gain: MaxDvgaGain::ALL,
+            max_lna_gain: MaxLnaGain::MAX,
+            magn_target: MagnTarget::DB_33,
+            lna_priority: AgcLnaPriority::LNA_FIRST,
+            hyst_level: HystLevel::MEDIUM,
+            wait_time: WaitTime::SAMPLES_16,
+            freeze: AgcFreeze::NORMAL,
+            filter_length: FilterLength::SAMPLES_16,
+        }
+    }
+}
+
+impl AgcConfig {
+    /// Keep all gain available and aim for a high amplitude, for weak signals on a quiet band.
+    pub fn best_sensitivity() -> Self {
+        AgcConfig {
+            magn_target: MagnTarget::DB_42,
+            ..AgcConfig::default()
+        }
+    }
+
+    /// Limit the gain and aim for a low amplitude, so strong nearby transmitters do not saturate
+    /// the receiver. Trades some sensitivity for blocking performance.
+    pub fn strong_interferer() -> Self {
+        AgcConfig {
+            max_dvga_gain: MaxDvgaGain::EXCLUDE_HIGHEST_2,
+            magn_target: MagnTarget::DB_24,
+            lna_priority: AgcLnaPriority::LNA_FIRST,
+            hyst_level: HystLevel::LARGE,
+            wait_time: WaitTime::SAMPLES_32,
+            filter_length: FilterLength::SAMPLES_32,
+            ..AgcConfig::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lowlevel::registers::*;
+    use crate::lowlevel::sim::{self, Chip};
+    use crate::lowlevel::types::*;
+    use crate::*;
+    use core::cell::RefCell;
+
+    #[test]
+    fn test_set_agc() {
+        let chip = RefCell::new(Chip::new());
+        let mut radio = sim::cc1101(&chip);
+        radio.set_carrier_sense_threshold(Some(-7), CarrierSenseRelThr::INCREASE_10DB).unwrap();
+
+        let presets = [
+            (AgcConfig::default(), [0x03, 0x69, 0x91]),
+            (AgcConfig::best_sensitivity(), [0x07, 0x69, 0x91]),
+            (AgcConfig::strong_interferer(), [0x80, 0x69, 0xF2]),
+        ];
+        for (agc, expected) in presets.iter() {
+            radio.set_agc(agc).unwrap();
+            let chip = chip.borrow();
+            let agcctrl = &chip.config[Config::AGCCTRL2 as usize..][..3];
+            assert_eq!(agcctrl, expected);
+        }
+
+        // The carrier sense thresholds are kept with LNA 2 first.
+        let lna2_first = AgcConfig {
+            lna_priority: AgcLnaPriority::LNA2_FIRST,
+            ..AgcConfig::default()
+        };
+        radio.set_agc(&lna2_first).unwrap();
+        assert_eq!(chip.borrow().config[Config::AGCCTRL1 as usize], 0x29);
+    }
+}
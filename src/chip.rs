@@ -0,0 +1,124 @@
+use crate::Modulation;
+
+/// Chip variants identified by their PARTNUM and VERSION status registers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChipVariant {
+    /// CC1100, predecessor of the CC1101.
+    CC1100,
+    /// CC1100E, the 470 MHz and 950 MHz variant of the CC1100.
+    CC1100E,
+    /// CC1101, carrying the revision reported in VERSION (0x04 or 0x14).
+    CC1101(u8),
+    /// CC110L value line transceiver.
+    CC110L,
+    /// CC113L value line receiver.
+    CC113L,
+    /// CC115L value line transmitter.
+    CC115L,
+}
+
+/// Result of identifying a chip from its PARTNUM and VERSION values.
+#[derive(Debug, PartialEq)]
+pub(crate) enum Identity {
+    /// Nothing responded on the bus, reads returned all zeroes or all ones.
+    Missing,
+    /// Something responded, but it is not a known part.
+    Unknown,
+    /// A known part.
+    Known(ChipVariant),
+}
+
+impl ChipVariant {
+    pub(crate) fn identify(partnum: u8, version: u8) -> Identity {
+        match (partnum, version) {
+            (0x00, 0x00) | (0xFF, 0xFF) => Identity::Missing,
+            (0x00, 0x03) => Identity::Known(ChipVariant::CC1100),
+            (0x00, 0x04) | (0x00, 0x14) => Identity::Known(ChipVariant::CC1101(version)),
+            (0x00, 0x05) => Identity::Known(ChipVariant::CC1100E),
+            (0x00, 0x07) => Identity::Known(ChipVariant::CC110L),
+            (0x00, 0x08) => Identity::Known(ChipVariant::CC113L),
+            (0x00, 0x09) => Identity::Known(ChipVariant::CC115L),
+            _ => Identity::Unknown,
+        }
+    }
+
+    /// Whether the chip has a receive chain.
+    pub fn can_receive(&self) -> bool {
+        !matches!(self, ChipVariant::CC115L)
+    }
+
+    /// Whether the chip has a transmit chain.
+    pub fn can_transmit(&self) -> bool {
+        !matches!(self, ChipVariant::CC113L)
+    }
+
+    /// Whether the chip supports the given modulation format.
+    pub fn supports_modulation(&self, modulation: &Modulation) -> bool {
+        match modulation {
+            // The value line parts lack MSK.
+            Modulation::MinimumShiftKeying => !self.is_value_line(),
+            // 4-FSK was added with the CC1101, and is kept in the value line.
+            Modulation::FourFrequencyShiftKeying => {
+                !matches!(self, ChipVariant::CC1100 | ChipVariant::CC1100E)
+            }
+            _ => true,
+        }
+    }
+
+    /// Whether the chip supports Forward Error Correction and interleaving.
+    pub fn supports_fec(&self) -> bool {
+        !self.is_value_line()
+    }
+
+    fn is_value_line(&self) -> bool {
+        matches!(self, ChipVariant::CC110L | ChipVariant::CC113L | ChipVariant::CC115L)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::chip::*;
+
+    #[test]
+    fn test_identify() {
+        let table = [
+            (0x00, 0x00, Identity::Missing),
+            (0xFF, 0xFF, Identity::Missing),
+            (0x00, 0x03, Identity::Known(ChipVariant::CC1100)),
+            (0x00, 0x04, Identity::Known(ChipVariant::CC1101(0x04))),
+            (0x00, 0x14, Identity::Known(ChipVariant::CC1101(0x14))),
+            (0x00, 0x05, Identity::Known(ChipVariant::CC1100E)),
+            (0x00, 0x07, Identity::Known(ChipVariant::CC110L)),
+            (0x00, 0x08, Identity::Known(ChipVariant::CC113L)),
+            (0x00, 0x09, Identity::Known(ChipVariant::CC115L)),
+            (0x00, 0x01, Identity::Unknown),
+            (0x80, 0x14, Identity::Unknown),
+            (0xFF, 0x00, Identity::Unknown),
+        ];
+        for (partnum, version, identity) in table.iter() {
+            assert_eq!(&ChipVariant::identify(*partnum, *version), identity);
+        }
+    }
+
+    #[test]
+    fn test_capabilities() {
+        assert!(ChipVariant::CC1101(0x14).can_receive());
+        assert!(ChipVariant::CC1101(0x14).can_transmit());
+        assert!(ChipVariant::CC110L.can_receive());
+        assert!(ChipVariant::CC110L.can_transmit());
+        assert!(ChipVariant::CC113L.can_receive());
+        assert!(!ChipVariant::CC113L.can_transmit());
+        assert!(!ChipVariant::CC115L.can_receive());
+        assert!(ChipVariant::CC115L.can_transmit());
+
+        let fsk4 = Modulation::FourFrequencyShiftKeying;
+        let msk = Modulation::MinimumShiftKeying;
+        assert!(ChipVariant::CC110L.supports_modulation(&fsk4));
+        assert!(!ChipVariant::CC110L.supports_modulation(&msk));
+        assert!(ChipVariant::CC1101(0x14).supports_modulation(&msk));
+        assert!(ChipVariant::CC1101(0x14).supports_modulation(&fsk4));
+        assert!(!ChipVariant::CC1100.supports_modulation(&fsk4));
+        assert!(!ChipVariant::CC1100E.supports_modulation(&fsk4));
+        assert!(ChipVariant::CC1100.supports_modulation(&msk));
+    }
+}
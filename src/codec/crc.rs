@@ -0,0 +1,55 @@
+/// CRC-16 polynomial used by the packet handler, x^16 + x^15 + x^2 + 1.
+const CRC16_POLY: u16 = 0x8005;
+
+/// Initial CRC value, also used when the CRC is reset for each new packet.
+pub const CRC16_INIT: u16 = 0xFFFF;
+
+/// Update a running CRC-16 with one byte, most significant bit first.
+pub const fn crc16_update(crc: u16, byte: u8) -> u16 {
+    let mut crc = crc;
+    let mut data = byte;
+    let mut i = 0;
+    while i < 8 {
+        if ((crc >> 8) as u8 ^ data) & 0x80 != 0 {
+            crc = (crc << 1) ^ CRC16_POLY;
+        } else {
+            crc <<= 1;
+        }
+        data <<= 1;
+        i += 1;
+    }
+    crc
+}
+
+/// CRC-16 of a packet, calculated over the length byte, address byte and payload.
+///
+/// The CRC is appended to the packet most significant byte first.
+pub const fn crc16(data: &[u8]) -> u16 {
+    let mut crc = CRC16_INIT;
+    let mut i = 0;
+    while i < data.len() {
+        crc = crc16_update(crc, data[i]);
+        i += 1;
+    }
+    crc
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::codec::*;
+
+    #[test]
+    fn test_crc16() {
+        // CRC-16/CMS check value.
+        assert_eq!(crc16(b"123456789"), 0xAEE7);
+        assert_eq!(crc16(&[]), CRC16_INIT);
+
+        // A packet followed by its CRC has zero remainder.
+        let packet = [0x03, 0x01, 0x02, 0x03];
+        let crc = crc16(&packet);
+        let mut checked = crc16(&packet);
+        checked = crc16_update(checked, (crc >> 8) as u8);
+        checked = crc16_update(checked, crc as u8);
+        assert_eq!(checked, 0);
+    }
+}
@@ -0,0 +1,213 @@
+use super::Error;
+
+/// Output of the rate 1/2, constraint length 4 convolutional encoder, indexed by the three
+/// previous input bits followed by the current input bit (DN504).
+const FEC_ENCODE_TABLE: [u8; 16] = [0, 3, 1, 2, 3, 0, 2, 1, 3, 0, 2, 1, 0, 3, 1, 2];
+
+/// Appended to the data to bring the encoder back to a known state.
+const TRELLIS_TERMINATOR: u8 = 0x0B;
+
+/// Number of decoded bits kept before deciding on the oldest byte in the Viterbi decoder.
+const TRACEBACK_DEPTH: u32 = 32;
+
+/// Number of bytes [`fec_encode`] produces from `len` bytes of data.
+pub const fn fec_encoded_len(len: usize) -> usize {
+    // Padded with one or two trellis terminator bytes to an even number of bytes.
+    4 * (len / 2 + 1)
+}
+
+/// FEC encode and interleave a packet.
+///
+/// The input is the packet as the packet handler sees it, length byte, address byte, payload
+/// and CRC. Returns the number of bytes written to `output`, see [`fec_encoded_len`].
+pub fn fec_encode(input: &[u8], output: &mut [u8]) -> Result<usize, Error> {
+    let len = fec_encoded_len(input.len());
+    if output.len() < len {
+        return Err(Error::BufferTooSmall);
+    }
+
+    let mut reg = 0u16;
+    for (i, out) in output[..len].chunks_exact_mut(2).enumerate() {
+        let byte = input.get(i).copied().unwrap_or(TRELLIS_TERMINATOR);
+        reg = (reg & 0x700) | byte as u16;
+
+        let mut symbols = 0u16;
+        for _ in 0..8 {
+            symbols = (symbols << 2) | FEC_ENCODE_TABLE[(reg >> 7) as usize] as u16;
+            reg = (reg << 1) & 0x7FF;
+        }
+        out.copy_from_slice(&symbols.to_be_bytes());
+    }
+
+    for block in output[..len].chunks_exact_mut(4) {
+        interleave(block);
+    }
+    Ok(len)
+}
+
+/// Result of [`fec_decode`].
+#[derive(Debug, PartialEq)]
+pub struct FecDecoded {
+    /// Number of bytes written to the output, including the trellis terminator.
+    pub len: usize,
+    /// Number of received bits that differ from the decoded path, a measure of link quality.
+    pub bit_errors: u32,
+}
+
+/// De-interleave and FEC decode a packet using a hard decision Viterbi decoder.
+///
+/// The input must be a multiple of the 4 byte interleaver block, and decodes into half as many
+/// bytes, including the one or two trellis terminator bytes following the CRC.
+pub fn fec_decode(input: &[u8], output: &mut [u8]) -> Result<FecDecoded, Error> {
+    if input.len() % 4 != 0 {
+        return Err(Error::InvalidLength);
+    }
+    let len = input.len() / 2;
+    if output.len() < len {
+        return Err(Error::BufferTooSmall);
+    }
+
+    // Path metrics and the most recent decoded bits of the surviving path into each state.
+    let mut metrics = [u32::MAX; 8];
+    let mut paths = [0u64; 8];
+    metrics[0] = 0;
+
+    let mut pending = 0;
+    let mut written = 0;
+    for block in input.chunks_exact(4) {
+        let mut symbols = [block[0], block[1], block[2], block[3]];
+        deinterleave(&mut symbols);
+
+        for byte in symbols.iter() {
+            for shift in [6, 4, 2, 0].iter() {
+                let symbol = (byte >> shift) & 0x03;
+
+                let mut next_metrics = [u32::MAX; 8];
+                let mut next_paths = [0u64; 8];
+                for state in 0..8 {
+                    if metrics[state] == u32::MAX {
+                        continue;
+                    }
+                    for bit in 0..2 {
+                        let index = (state << 1) | bit;
+                        let next = index & 0x07;
+                        let cost = (FEC_ENCODE_TABLE[index] ^ symbol).count_ones();
+                        let metric = metrics[state] + cost;
+                        if metric < next_metrics[next] {
+                            next_metrics[next] = metric;
+                            next_paths[next] = (paths[state] << 1) | bit as u64;
+                        }
+                    }
+                }
+                metrics = next_metrics;
+                paths = next_paths;
+                pending += 1;
+
+                if pending == TRACEBACK_DEPTH + 8 {
+                    let best = best_state(&metrics);
+                    output[written] = (paths[best] >> (pending - 8)) as u8;
+                    written += 1;
+                    pending -= 8;
+                }
+            }
+        }
+    }
+
+    let best = best_state(&metrics);
+    while pending >= 8 {
+        output[written] = (paths[best] >> (pending - 8)) as u8;
+        written += 1;
+        pending -= 8;
+    }
+
+    Ok(FecDecoded {
+        len: written,
+        bit_errors: metrics[best],
+    })
+}
+
+fn best_state(metrics: &[u32; 8]) -> usize {
+    let mut best = 0;
+    for state in 1..8 {
+        if metrics[state] < metrics[best] {
+            best = state;
+        }
+    }
+    best
+}
+
+/// Interleave a block of 4 bytes (16 symbols) as a 4x4 matrix, written row by row and read
+/// column by column.
+fn interleave(block: &mut [u8]) {
+    let input = [block[0], block[1], block[2], block[3]];
+    let mut word = 0u32;
+    for j in 0..16 {
+        let symbol = (input[3 - (j & 0x03)] >> (2 * (j >> 2))) & 0x03;
+        word = (word << 2) | symbol as u32;
+    }
+    block.copy_from_slice(&word.to_be_bytes());
+}
+
+/// Reverse of [`interleave`].
+fn deinterleave(block: &mut [u8]) {
+    let word = u32::from_be_bytes([block[0], block[1], block[2], block[3]]);
+    let mut output = [0u8; 4];
+    for j in 0..16 {
+        let symbol = ((word >> (2 * (15 - j))) & 0x03) as u8;
+        output[3 - (j & 0x03)] |= symbol << (2 * (j >> 2));
+    }
+    block.copy_from_slice(&output);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::codec::*;
+
+    // Length 3, payload 1, 2, 3 and CRC.
+    const PACKET: [u8; 6] = [0x03, 0x01, 0x02, 0x03, 0x30, 0x3A];
+    const ENCODED: [u8; 16] = [
+        0xC8, 0x3C, 0x00, 0x20, 0x84, 0xCF, 0x33, 0x31, 0xA2, 0xFC, 0x40, 0x4A, 0x44, 0x30, 0x47,
+        0xEF,
+    ];
+
+    #[test]
+    fn test_fec_encode() {
+        assert_eq!(crc16(&PACKET[..4]), 0x303A);
+
+        let mut encoded = [0u8; 16];
+        assert_eq!(fec_encode(&PACKET, &mut encoded), Ok(16));
+        assert_eq!(encoded, ENCODED);
+
+        assert_eq!(fec_encoded_len(5), 12);
+        assert_eq!(fec_encode(&PACKET, &mut [0u8; 15]), Err(Error::BufferTooSmall));
+    }
+
+    #[test]
+    fn test_fec_decode() {
+        let mut decoded = [0u8; 8];
+        let result = fec_decode(&ENCODED, &mut decoded).unwrap();
+        assert_eq!(result.len, 8);
+        assert_eq!(result.bit_errors, 0);
+        assert_eq!(decoded[..6], PACKET);
+        assert_eq!(decoded[6..], [0x0B, 0x0B]);
+
+        assert_eq!(fec_decode(&ENCODED[..15], &mut decoded), Err(Error::InvalidLength));
+    }
+
+    #[test]
+    fn test_fec_decode_corrects_errors() {
+        let payload = *b"\x10The quick brown fox jumps";
+        let mut encoded = [0u8; 56];
+        assert_eq!(fec_encode(&payload, &mut encoded), Ok(56));
+
+        // Flip isolated bits, spaced further apart than the constraint length.
+        for i in [0, 9, 20, 33, 47].iter() {
+            encoded[*i] ^= 0x10;
+        }
+
+        let mut decoded = [0u8; 28];
+        let result = fec_decode(&encoded, &mut decoded).unwrap();
+        assert_eq!(result.bit_errors, 5);
+        assert_eq!(decoded[..payload.len()], payload);
+    }
+}
@@ -0,0 +1,21 @@
+//! Software implementations of the CC1101 packet handler coding.
+//!
+//! Useful with infinite packet length or serial data modes, where the packet handler is not
+//! used, and for decoding captured frames offline.
+
+mod crc;
+mod fec;
+mod whitening;
+
+pub use self::crc::*;
+pub use self::fec::*;
+pub use self::whitening::*;
+
+/// Codec errors.
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    /// The output buffer is too small for the result.
+    BufferTooSmall,
+    /// The input length is not valid for the operation.
+    InvalidLength,
+}
@@ -0,0 +1,59 @@
+/// PN9 sequence generator used for data whitening, x^9 + x^5 + 1 seeded with all ones.
+#[derive(Clone)]
+pub struct Pn9(u16);
+
+impl Pn9 {
+    pub const fn new() -> Self {
+        Pn9(0x1FF)
+    }
+
+    /// Next byte of the sequence.
+    pub fn next_byte(&mut self) -> u8 {
+        let byte = (self.0 & 0xFF) as u8;
+        for _ in 0..8 {
+            let bit = (self.0 ^ (self.0 >> 5)) & 1;
+            self.0 = (self.0 >> 1) | (bit << 8);
+        }
+        byte
+    }
+}
+
+impl Default for Pn9 {
+    fn default() -> Self {
+        Pn9::new()
+    }
+}
+
+impl Iterator for Pn9 {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        Some(self.next_byte())
+    }
+}
+
+/// Whiten, or de-whiten, data in place by XOR-ing it with the PN9 sequence.
+///
+/// Whitening covers everything after the sync word, including the length byte and CRC, and is
+/// applied before FEC encoding in TX and after FEC decoding in RX.
+pub fn whiten(data: &mut [u8]) {
+    let mut pn9 = Pn9::new();
+    for byte in data.iter_mut() {
+        *byte ^= pn9.next_byte();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::codec::*;
+
+    #[test]
+    fn test_pn9() {
+        let mut data = [0u8; 12];
+        whiten(&mut data);
+        assert_eq!(data, [0xFF, 0xE1, 0x1D, 0x9A, 0xED, 0x85, 0x33, 0x24, 0xEA, 0x7A, 0xD2, 0x39]);
+
+        whiten(&mut data);
+        assert_eq!(data, [0u8; 12]);
+    }
+}
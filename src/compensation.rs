@@ -0,0 +1,136 @@
+use crate::lowlevel::types::*;
+
+/// Frequency offset compensation loop configuration (FOCCFG).
+#[derive(Clone, Copy)]
+pub struct FrequencyCompensation {
+    /// Freeze the compensation and clock recovery loops until carrier sense is asserted.
+    pub carrier_sense_gate: bool,
+    /// Loop gain before a sync word is detected.
+    pub pre_k: FocPreK,
+    /// Loop gain after a sync word is detected.
+    pub post_k: FocPostK,
+    /// Saturation point of the compensation, must be disabled for ASK/OOK.
+    pub limit: FocLimit,
+}
+
+impl Default for FrequencyCompensation {
+    /// Reset value of FOCCFG.
+    fn default() -> Self {
+        FrequencyCompensation {
+            carrier_sense_gate: true,
+            pre_k: FocPreK::K_3,
+            post_k: FocPostK::K_HALF,
+            limit: FocLimit::BW_DIV_4,
+        }
+    }
+}
+
+impl FrequencyCompensation {
+    /// Settings SmartRF Studio recommends for the given data rate and channel filter
+    /// bandwidth, FOCCFG = 0x1D from 100 kBaud and 0x16 below.
+    ///
+    /// Below 100 kBaud the limit is lowered to BW/8 when the filter is narrower than twice the
+    /// data rate, where a BW/4 correction would move the signal out of the filter.
+    pub fn recommended(baud: u64, bandwidth: u64) -> Self {
+        let (pre_k, limit) = if baud >= 100_000 {
+            (FocPreK::K_4, FocLimit::BW_DIV_8)
+        } else if bandwidth < 2 * baud {
+            (FocPreK::K_3, FocLimit::BW_DIV_8)
+        } else {
+            (FocPreK::K_3, FocLimit::BW_DIV_4)
+        };
+        FrequencyCompensation {
+            carrier_sense_gate: false,
+            pre_k,
+            post_k: FocPostK::K_HALF,
+            limit,
+        }
+    }
+
+    /// No frequency offset compensation, as required for ASK/OOK.
+    pub fn disabled() -> Self {
+        FrequencyCompensation {
+            carrier_sense_gate: false,
+            pre_k: FocPreK::K_3,
+            post_k: FocPostK::K_HALF,
+            limit: FocLimit::DISABLED,
+        }
+    }
+}
+
+/// Bit synchronization (clock recovery) loop configuration (BSCFG).
+#[derive(Clone, Copy)]
+pub struct BitSync {
+    /// Integral gain before a sync word is detected.
+    pub pre_ki: BsPreKi,
+    /// Proportional gain before a sync word is detected.
+    pub pre_kp: BsPreKp,
+    /// Integral gain after a sync word is detected.
+    pub post_ki: BsPostKi,
+    /// Proportional gain after a sync word is detected.
+    pub post_kp: BsPostKp,
+    /// Saturation point of the data rate offset compensation.
+    pub limit: BsLimit,
+}
+
+impl Default for BitSync {
+    /// Reset value of BSCFG.
+    fn default() -> Self {
+        BitSync {
+            pre_ki: BsPreKi::KI_2,
+            pre_kp: BsPreKp::KP_3,
+            post_ki: BsPostKi::KI_HALF,
+            post_kp: BsPostKp::KP_1,
+            limit: BsLimit::DISABLED,
+        }
+    }
+}
+
+impl BitSync {
+    /// Settings SmartRF Studio recommends for the given data rate, BSCFG = 0x1C from 100 kBaud
+    /// and 0x6C below. Unlike FOC_LIMIT, the clock recovery loop does not depend on the channel
+    /// filter bandwidth.
+    pub fn recommended(baud: u64) -> Self {
+        if baud >= 100_000 {
+            BitSync {
+                pre_ki: BsPreKi::KI_1,
+                pre_kp: BsPreKp::KP_2,
+                post_ki: BsPostKi::KI_HALF,
+                post_kp: BsPostKp::KP_1,
+                limit: BsLimit::DISABLED,
+            }
+        } else {
+            BitSync::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lowlevel::registers::*;
+    use crate::lowlevel::sim::{self, Chip};
+    use crate::*;
+    use core::cell::RefCell;
+
+    #[test]
+    fn test_smartrf_presets() {
+        let chip = RefCell::new(Chip::new());
+        let mut radio = sim::cc1101(&chip);
+
+        // 1.2 kBaud, 38.4 kBaud and 250 kBaud presets, with their filter bandwidths.
+        let presets = [
+            (1_200, 58_035, 0x16, 0x6C),
+            (38_400, 101_562, 0x16, 0x6C),
+            (250_000, 541_666, 0x1D, 0x1C),
+            // A filter narrower than twice the data rate lowers the limit to BW/8.
+            (38_400, 58_035, 0x15, 0x6C),
+        ];
+        for &(baud, bandwidth, foccfg, bscfg) in presets.iter() {
+            let compensation = FrequencyCompensation::recommended(baud, bandwidth);
+            radio.set_frequency_compensation(&compensation).unwrap();
+            radio.set_bit_sync(&BitSync::recommended(baud)).unwrap();
+            assert_eq!(chip.borrow().config[Config::FOCCFG as usize], foccfg);
+            assert_eq!(chip.borrow().config[Config::BSCFG as usize], bscfg);
+        }
+    }
+}
@@ -0,0 +1,417 @@
+//! Flipper Zero SubGhz `.sub` files.
+//!
+//! Files are parsed with [`SubFile::parse`] and written with its `Display` implementation. The
+//! preset maps onto [`RegisterSettings`], and `RAW_Data` onto [`Pulse`]s, so a recording can be
+//! replayed with [`Cc1101::transmit_sub_file`](crate::Cc1101::transmit_sub_file).
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::lowlevel::convert::from_frequency;
+use crate::lowlevel::registers::Config;
+use crate::ook::Pulse;
+use crate::RegisterSettings;
+
+/// Name of the preset with register values in `Custom_preset_data`.
+pub const CUSTOM_PRESET: &str = "FuriHalSubGhzPresetCustom";
+
+/// PA table written for custom presets without one, the driver default.
+const DEFAULT_PA_TABLE: [u8; 8] = [0x00, 0xC0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
+
+/// Number of values per `RAW_Data` line, as written by the Flipper firmware.
+const RAW_DATA_PER_LINE: usize = 512;
+
+/// Preset of a `.sub` file.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Preset {
+    /// One of the firmware presets, such as `FuriHalSubGhzPresetOok650Async`.
+    Named(String),
+    /// Register values and PA table from `Custom_preset_data`.
+    Custom(RegisterSettings),
+}
+
+impl Preset {
+    /// Register settings of the preset, `None` for unknown firmware presets.
+    pub fn settings(&self) -> Option<RegisterSettings> {
+        match self {
+            Preset::Named(name) => firmware_preset(name),
+            Preset::Custom(settings) => Some(settings.clone()),
+        }
+    }
+}
+
+/// A Flipper Zero SubGhz recording or key file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SubFile {
+    /// Frequency in Hz.
+    pub frequency: u64,
+    pub preset: Preset,
+    /// `RAW` for recordings, or the name of a decoded protocol.
+    pub protocol: String,
+    /// Durations in microseconds, positive with carrier on and negative with carrier off.
+    pub raw_data: Vec<i32>,
+    /// Other fields in file order, such as `Bit` and `Key` of decoded protocols.
+    pub fields: Vec<(String, String)>,
+}
+
+/// `.sub` file parse errors.
+#[derive(Debug, PartialEq)]
+pub enum ParseError {
+    /// Not a Flipper SubGhz file.
+    UnsupportedFiletype,
+    /// A required field is missing.
+    MissingField(&'static str),
+    /// The line, counting from 1, could not be parsed.
+    InvalidLine(usize),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::UnsupportedFiletype => write!(f, "not a Flipper SubGhz file"),
+            ParseError::MissingField(field) => write!(f, "missing field {}", field),
+            ParseError::InvalidLine(line) => write!(f, "invalid line {}", line),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseError {}
+
+impl SubFile {
+    /// A `RAW` recording of captured pulses.
+    pub fn from_pulses(frequency: u64, preset: Preset, pulses: &[Pulse]) -> Self {
+        let raw_data = pulses
+            .iter()
+            .map(|p| {
+                let duration = p.duration_us.min(i32::MAX as u32) as i32;
+                if p.level {
+                    duration
+                } else {
+                    -duration
+                }
+            })
+            .collect();
+        SubFile {
+            frequency,
+            preset,
+            protocol: "RAW".to_string(),
+            raw_data,
+            fields: Vec::new(),
+        }
+    }
+
+    /// Parse the contents of a `.sub` file.
+    pub fn parse(text: &str) -> Result<Self, ParseError> {
+        let mut filetype = false;
+        let mut frequency = None;
+        let mut preset = None;
+        let mut custom = None;
+        let mut protocol = None;
+        let mut raw_data = Vec::new();
+        let mut fields = Vec::new();
+
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = ParseError::InvalidLine(i + 1);
+            let (key, value) = match line.split_once(':') {
+                Some((key, value)) => (key.trim(), value.trim()),
+                None => return Err(invalid),
+            };
+            match key {
+                "Filetype" if value.starts_with("Flipper SubGhz") => filetype = true,
+                "Filetype" => return Err(ParseError::UnsupportedFiletype),
+                "Version" => {}
+                "Frequency" => frequency = Some(value.parse().map_err(|_| invalid)?),
+                "Preset" => preset = Some(value),
+                "Custom_preset_module" if value == "CC1101" => {}
+                "Custom_preset_module" => return Err(invalid),
+                "Custom_preset_data" => custom = Some(parse_custom_preset(value).ok_or(invalid)?),
+                "Protocol" => protocol = Some(value.to_string()),
+                "RAW_Data" => {
+                    // The Flipper never writes zero durations.
+                    for duration in value.split_whitespace() {
+                        match duration.parse() {
+                            Ok(0) | Err(_) => return Err(invalid),
+                            Ok(duration) => raw_data.push(duration),
+                        }
+                    }
+                }
+                _ => fields.push((key.to_string(), value.to_string())),
+            }
+        }
+
+        if !filetype {
+            return Err(ParseError::UnsupportedFiletype);
+        }
+        let preset = match preset.ok_or(ParseError::MissingField("Preset"))? {
+            CUSTOM_PRESET => {
+                Preset::Custom(custom.ok_or(ParseError::MissingField("Custom_preset_data"))?)
+            }
+            name => Preset::Named(name.to_string()),
+        };
+        Ok(SubFile {
+            frequency: frequency.ok_or(ParseError::MissingField("Frequency"))?,
+            preset,
+            protocol: protocol.ok_or(ParseError::MissingField("Protocol"))?,
+            raw_data,
+            fields,
+        })
+    }
+
+    /// Register settings of the preset with the frequency, `None` for unknown firmware presets.
+    pub fn settings(&self) -> Option<RegisterSettings> {
+        let (freq0, freq1, freq2) = from_frequency(self.frequency);
+        let mut settings = self.preset.settings()?;
+        settings.set(Config::FREQ2, freq2);
+        settings.set(Config::FREQ1, freq1);
+        settings.set(Config::FREQ0, freq0);
+        Some(settings)
+    }
+
+    /// `RAW_Data` as pulses, for [`Waveform::Pulses`](crate::ook::Waveform::Pulses).
+    pub fn pulses(&self) -> impl Iterator<Item = Pulse> + '_ {
+        self.raw_data.iter().map(|&duration| Pulse {
+            level: duration > 0,
+            duration_us: duration.unsigned_abs(),
+        })
+    }
+}
+
+impl fmt::Display for SubFile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let filetype = if self.protocol == "RAW" {
+            "RAW File"
+        } else {
+            "Key File"
+        };
+        writeln!(f, "Filetype: Flipper SubGhz {}", filetype)?;
+        writeln!(f, "Version: 1")?;
+        writeln!(f, "Frequency: {}", self.frequency)?;
+        match &self.preset {
+            Preset::Named(name) => writeln!(f, "Preset: {}", name)?,
+            Preset::Custom(settings) => {
+                writeln!(f, "Preset: {}", CUSTOM_PRESET)?;
+                writeln!(f, "Custom_preset_module: CC1101")?;
+                write!(f, "Custom_preset_data:")?;
+                for (reg, value) in settings.iter() {
+                    write!(f, " {:02X} {:02X}", reg.addr(), value)?;
+                }
+                write!(f, " 00 00")?;
+                for value in settings.pa_table().unwrap_or(DEFAULT_PA_TABLE).iter() {
+                    write!(f, " {:02X}", value)?;
+                }
+                writeln!(f)?;
+            }
+        }
+        writeln!(f, "Protocol: {}", self.protocol)?;
+        for (key, value) in self.fields.iter() {
+            writeln!(f, "{}: {}", key, value)?;
+        }
+        for line in self.raw_data.chunks(RAW_DATA_PER_LINE) {
+            write!(f, "RAW_Data:")?;
+            for duration in line.iter() {
+                write!(f, " {}", duration)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Register address and value pairs, terminated by `00 00`, followed by the 8 byte PA table.
+fn parse_custom_preset(data: &str) -> Option<RegisterSettings> {
+    let mut bytes = data.split_whitespace().map(|byte| u8::from_str_radix(byte, 16));
+
+    let mut settings = RegisterSettings::new();
+    loop {
+        let addr = bytes.next()?.ok()?;
+        let value = bytes.next()?.ok()?;
+        if addr == 0 && value == 0 {
+            break;
+        }
+        settings.set(Config::from_addr(addr)?, value);
+    }
+
+    let mut pa_table = [0u8; 8];
+    for entry in pa_table.iter_mut() {
+        *entry = bytes.next()?.ok()?;
+    }
+    if bytes.next().is_some() {
+        return None;
+    }
+    settings.set_pa_table(pa_table);
+    Some(settings)
+}
+
+/// Register values of the Flipper firmware presets.
+fn firmware_preset(name: &str) -> Option<RegisterSettings> {
+    // Asynchronous serial mode on GDO0, infinite packet length, no whitening.
+    const ASYNC: RegisterSettings = RegisterSettings::new()
+        .with(Config::IOCFG0, 0x0D)
+        .with(Config::PKTCTRL0, 0x32)
+        .with(Config::FSCTRL1, 0x06)
+        .with(Config::MDMCFG0, 0x00)
+        .with(Config::MCSM0, 0x18)
+        .with(Config::WORCTRL, 0xFB);
+    const OOK_PA_TABLE: [u8; 8] = [0x00, 0xC0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
+    const FSK_PA_TABLE: [u8; 8] = [0xC0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
+
+    const OOK_270: RegisterSettings = ASYNC
+        .with(Config::FIFOTHR, 0x47)
+        .with(Config::MDMCFG1, 0x00)
+        .with(Config::MDMCFG2, 0x30)
+        .with(Config::MDMCFG3, 0x32)
+        .with(Config::MDMCFG4, 0x67)
+        .with(Config::FOCCFG, 0x18)
+        .with(Config::AGCCTRL0, 0x40)
+        .with(Config::AGCCTRL1, 0x00)
+        .with(Config::AGCCTRL2, 0x03)
+        .with(Config::FREND0, 0x11)
+        .with(Config::FREND1, 0xB6)
+        .with_pa_table(OOK_PA_TABLE);
+    const OOK_650: RegisterSettings = OOK_270
+        .with(Config::FIFOTHR, 0x07)
+        .with(Config::MDMCFG4, 0x17)
+        .with(Config::AGCCTRL0, 0x91)
+        .with(Config::AGCCTRL2, 0x07);
+    const FSK_238: RegisterSettings = ASYNC
+        .with(Config::FIFOTHR, 0x47)
+        .with(Config::MDMCFG1, 0x02)
+        .with(Config::MDMCFG2, 0x04)
+        .with(Config::MDMCFG3, 0x83)
+        .with(Config::MDMCFG4, 0x67)
+        .with(Config::DEVIATN, 0x04)
+        .with(Config::FOCCFG, 0x16)
+        .with(Config::AGCCTRL0, 0x91)
+        .with(Config::AGCCTRL1, 0x00)
+        .with(Config::AGCCTRL2, 0x07)
+        .with(Config::FREND0, 0x10)
+        .with(Config::FREND1, 0x56)
+        .with_pa_table(FSK_PA_TABLE);
+    const FSK_476: RegisterSettings = FSK_238.with(Config::DEVIATN, 0x47);
+
+    match name {
+        "FuriHalSubGhzPresetOok270Async" => Some(OOK_270),
+        "FuriHalSubGhzPresetOok650Async" => Some(OOK_650),
+        "FuriHalSubGhzPreset2FSKDev238Async" => Some(FSK_238),
+        "FuriHalSubGhzPreset2FSKDev476Async" => Some(FSK_476),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::flipper::*;
+    use alloc::string::ToString;
+    use alloc::vec;
+
+    const RAW: &str = "Filetype: Flipper SubGhz RAW File
+Version: 1
+Frequency: 433920000
+Preset: FuriHalSubGhzPresetOok650Async
+Protocol: RAW
+RAW_Data: 400 -1200 1200 -400
+RAW_Data: 400 -12000
+";
+
+    const CUSTOM: &str = "Filetype: Flipper SubGhz Key File
+Version: 1
+Frequency: 868350000
+Preset: FuriHalSubGhzPresetCustom
+Custom_preset_module: CC1101
+Custom_preset_data: 02 0D 08 32 12 30 00 00 00 C0 00 00 00 00 00 00
+Protocol: Princeton
+Bit: 24
+Key: 00 00 00 00 00 95 D5 D4
+TE: 400
+";
+
+    #[test]
+    fn test_parse_raw() {
+        let file = SubFile::parse(RAW).unwrap();
+        assert_eq!(file.frequency, 433_920_000);
+        assert_eq!(file.preset, Preset::Named("FuriHalSubGhzPresetOok650Async".to_string()));
+        assert_eq!(file.protocol, "RAW");
+        assert_eq!(file.raw_data, vec![400, -1200, 1200, -400, 400, -12000]);
+        assert_eq!(
+            file.pulses().nth(1),
+            Some(Pulse {
+                level: false,
+                duration_us: 1200,
+            })
+        );
+
+        let settings = file.settings().unwrap();
+        assert_eq!(settings.get(Config::MDMCFG4), Some(0x17));
+        assert_eq!(settings.get(Config::FREQ2), Some(0x10));
+        assert_eq!(settings.pa_table(), Some([0x00, 0xC0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]));
+
+        assert_eq!(file.to_string(), RAW.replace("-400\nRAW_Data:", "-400"));
+    }
+
+    #[test]
+    fn test_parse_custom() {
+        let file = SubFile::parse(CUSTOM).unwrap();
+        let settings = match &file.preset {
+            Preset::Custom(settings) => settings,
+            _ => panic!("expected custom preset"),
+        };
+        assert_eq!(settings.get(Config::IOCFG0), Some(0x0D));
+        assert_eq!(settings.get(Config::PKTCTRL0), Some(0x32));
+        assert_eq!(settings.get(Config::MDMCFG2), Some(0x30));
+        assert_eq!(settings.iter().count(), 3);
+        assert_eq!(file.fields.len(), 3);
+        assert!(file.raw_data.is_empty());
+
+        assert_eq!(file.to_string(), CUSTOM);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            SubFile::parse("Filetype: Flipper NFC device"),
+            Err(ParseError::UnsupportedFiletype)
+        );
+        assert_eq!(
+            SubFile::parse(&RAW.replace("Frequency: 433920000\n", "")),
+            Err(ParseError::MissingField("Frequency"))
+        );
+        assert_eq!(SubFile::parse(&RAW.replace("-1200", "x")), Err(ParseError::InvalidLine(6)));
+        assert_eq!(SubFile::parse(&RAW.replace("-1200", "0")), Err(ParseError::InvalidLine(6)));
+        // Status register address, and a short PA table.
+        assert_eq!(
+            SubFile::parse(&CUSTOM.replace("12 30", "F5 30")),
+            Err(ParseError::InvalidLine(6))
+        );
+        assert_eq!(
+            SubFile::parse(&CUSTOM.replace("00 00\nProtocol", "\nProtocol")),
+            Err(ParseError::InvalidLine(6))
+        );
+    }
+
+    #[test]
+    fn test_from_pulses() {
+        let pulses = [
+            Pulse {
+                level: true,
+                duration_us: 350,
+            },
+            Pulse {
+                level: false,
+                duration_us: 1050,
+            },
+        ];
+        let file = SubFile::from_pulses(
+            433_920_000,
+            Preset::Named("FuriHalSubGhzPresetOok270Async".to_string()),
+            &pulses,
+        );
+        assert_eq!(file.raw_data, vec![350, -1050]);
+        assert_eq!(SubFile::parse(&file.to_string()), Ok(file));
+    }
+}
@@ -0,0 +1,79 @@
+/// Listen-before-talk configuration for [`Cc1101::transmit_lbt`](crate::Cc1101::transmit_lbt).
+#[derive(Clone, Copy, Debug)]
+pub struct ListenBeforeTalk {
+    /// Time to listen in RX before each transmit attempt, in microseconds.
+    pub listen_us: u32,
+    /// Backoff slot length in microseconds, the backoff is a random number of slots.
+    pub backoff_slot_us: u32,
+    /// Upper bound of the random number of backoff slots, doubled on every retry.
+    pub max_backoff_slots: u16,
+    /// Number of transmit attempts before giving up.
+    pub attempts: u8,
+}
+
+impl Default for ListenBeforeTalk {
+    /// ETSI EN 300 220 style timing, 5 ms minimum listen time and up to 5 ms random backoff.
+    fn default() -> Self {
+        ListenBeforeTalk {
+            listen_us: 5_000,
+            backoff_slot_us: 500,
+            max_backoff_slots: 10,
+            attempts: 5,
+        }
+    }
+}
+
+/// Small xorshift generator for backoff slots, seeded from RSSI noise.
+pub(crate) struct Backoff(u16);
+
+impl Backoff {
+    pub(crate) fn new() -> Self {
+        Backoff(0xACE1)
+    }
+
+    pub(crate) fn mix(&mut self, noise: u8) {
+        self.0 ^= noise as u16;
+        // xorshift gets stuck at zero.
+        if self.0 == 0 {
+            self.0 = 0xACE1;
+        }
+    }
+
+    /// Random delay in microseconds for the given (zero based) attempt.
+    pub(crate) fn delay_us(&mut self, lbt: &ListenBeforeTalk, attempt: u8) -> u32 {
+        self.0 ^= self.0 << 7;
+        self.0 ^= self.0 >> 9;
+        self.0 ^= self.0 << 8;
+
+        let window = (lbt.max_backoff_slots as u32).saturating_mul(1 << attempt.min(8)).max(1);
+        (self.0 as u32 % window).saturating_mul(lbt.backoff_slot_us)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lbt::*;
+
+    #[test]
+    fn test_backoff() {
+        let lbt = ListenBeforeTalk::default();
+        let mut backoff = Backoff::new();
+        for attempt in 0..16 {
+            let window = lbt.max_backoff_slots as u32 * (1 << attempt.min(8));
+            let delay = backoff.delay_us(&lbt, attempt);
+            assert_eq!(delay % lbt.backoff_slot_us, 0);
+            assert!(delay < window * lbt.backoff_slot_us);
+        }
+
+        // Mixing in noise that cancels the state does not leave it stuck at zero.
+        let mut backoff = Backoff(0x00AA);
+        backoff.mix(0xAA);
+        assert_ne!(backoff.0, 0);
+
+        let none = ListenBeforeTalk {
+            max_backoff_slots: 0,
+            ..lbt
+        };
+        assert_eq!(Backoff::new().delay_us(&none, 0), 0);
+    }
+}
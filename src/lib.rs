@@ -1,28 +1,76 @@
 #![no_std]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
 extern crate embedded_hal as hal;
+#[cfg(feature = "std")]
+extern crate std;
 
+use hal::blocking::delay::DelayUs;
 use hal::blocking::spi::{Transfer, Write};
 use hal::digital::v2::OutputPin;
 
 #[macro_use]
 pub mod lowlevel;
+mod agc;
+mod chip;
+pub mod codec;
+mod compensation;
+#[cfg(feature = "alloc")]
+pub mod flipper;
+mod lbt;
+pub mod ook;
+mod packet;
 mod rssi;
+mod scan;
+mod settings;
+pub mod smartrf;
 
+pub use agc::AgcConfig;
+pub use chip::ChipVariant;
+use chip::Identity;
+pub use compensation::{BitSync, FrequencyCompensation};
+use lbt::Backoff;
+pub use lbt::ListenBeforeTalk;
 use lowlevel::convert::*;
+pub use lowlevel::dump::RegisterDump;
 use lowlevel::registers::*;
 use lowlevel::types::*;
-use rssi::rssi_to_dbm;
+use ook::Waveform;
+use packet::manchester_supported;
+pub use packet::PacketHandling;
+use rssi::{rssi_offset, rssi_to_dbm, RSSI_OFFSET};
+pub use scan::{ScanRange, ScanSample, Scanner};
+pub use settings::RegisterSettings;
 
 /// CC1101 errors.
 #[derive(Debug)]
 pub enum Error<SpiE, GpioE> {
     /// The RX FIFO buffer overflowed, too small buffer for configured packet length.
     RxOverflow,
+    /// The received packet does not fit in the given buffer.
+    BufferTooSmall,
     /// Corrupt packet received with invalid CRC.
     CrcMismatch,
     /// Unknown state
     UnknownState,
+    /// No chip responded, reads returned all zeroes or all ones.
+    ChipNotFound,
+    /// The chip responded with an unknown part number and version.
+    UnknownChip {
+        partnum: u8,
+        version: u8,
+    },
+    /// The operation is not supported by the detected chip variant.
+    Unsupported,
+    /// An argument was outside of the range supported by the chip.
+    InvalidArgument,
+    /// The channel stayed busy for all listen-before-talk attempts.
+    ChannelBusy,
+    /// No packet was received before the timeout expired.
+    Timeout,
+    /// The requested settings can not be combined with the current configuration.
+    InvalidConfiguration,
     /// Platform-dependent SPI-errors, such as IO errors.
     Spi(SpiE),
     /// Platform-dependent GPIO-errors, such as IO errors.
@@ -34,12 +82,28 @@ impl<SpiE, GpioE> From<lowlevel::Error<SpiE, GpioE>> for Error<SpiE, GpioE> {
         match e {
             lowlevel::Error::Spi(inner) => Error::Spi(inner),
             lowlevel::Error::Gpio(inner) => Error::Gpio(inner),
+            lowlevel::Error::InvalidArgument => Error::InvalidArgument,
         }
     }
 }
 
+// MARCSTATE is polled for up to 100 us after STX in RX, covering the RX to TX turnaround.
+const TX_TURNAROUND_POLLS: u32 = 10;
+const TX_TURNAROUND_POLL_US: u32 = 10;
+
 /// High level API for interacting with the CC1101 radio chip.
-pub struct Cc1101<SPI, CS>(lowlevel::Cc1101<SPI, CS>);
+pub struct Cc1101<SPI, CS> {
+    radio: lowlevel::Cc1101<SPI, CS>,
+    variant: Option<ChipVariant>,
+    // RSSI offset for the configured data rate and band, and the board specific calibration.
+    rssi_offset: i16,
+    rssi_calibration: i16,
+    // Accumulate the frequency offset estimate into FSCTRL0 after good packets.
+    afc: bool,
+    // States the radio enters after receiving and sending a packet (MCSM1).
+    rxoff_mode: RxOffMode,
+    txoff_mode: TxOffMode,
+}
 
 impl<SPI, CS, SpiE, GpioE> Cc1101<SPI, CS>
 where
@@ -47,38 +111,65 @@ where
     CS: OutputPin<Error = GpioE>,
 {
     pub fn new(spi: SPI, cs: CS) -> Result<Self, Error<SpiE, GpioE>> {
-        Ok(Cc1101(lowlevel::Cc1101::new(spi, cs)?))
+        Ok(Cc1101 {
+            radio: lowlevel::Cc1101::new(spi, cs)?,
+            variant: None,
+            rssi_offset: RSSI_OFFSET,
+            rssi_calibration: 0,
+            afc: false,
+            rxoff_mode: RxOffMode::IDLE,
+            txoff_mode: TxOffMode::IDLE,
+        })
+    }
+
+    /// Identify the connected chip, and restrict the driver to the features it supports.
+    pub fn probe(&mut self) -> Result<ChipVariant, Error<SpiE, GpioE>> {
+        let (partnum, version) = self.get_hw_info()?;
+        match ChipVariant::identify(partnum, version) {
+            Identity::Missing => Err(Error::ChipNotFound),
+            Identity::Unknown => Err(Error::UnknownChip {
+                partnum,
+                version,
+            }),
+            Identity::Known(variant) => {
+                self.variant = Some(variant);
+                Ok(variant)
+            }
+        }
+    }
+
+    /// The chip variant detected by [`Cc1101::probe`], if any.
+    pub fn variant(&self) -> Option<ChipVariant> {
+        self.variant
+    }
+
+    fn require<F>(&self, supported: F) -> Result<(), Error<SpiE, GpioE>>
+    where
+        F: FnOnce(&ChipVariant) -> bool,
+    {
+        match self.variant {
+            Some(ref variant) if !supported(variant) => Err(Error::Unsupported),
+            _ => Ok(()),
+        }
     }
 
     pub fn set_frequency(&mut self, hz: u64) -> Result<(), Error<SpiE, GpioE>> {
-        let (freq0, freq1, freq2) = from_frequency(hz);
-        self.0.write_register(Config::FREQ0, freq0)?;
-        self.0.write_register(Config::FREQ1, freq1)?;
-        self.0.write_register(Config::FREQ2, freq2)?;
+        self.radio.write_composite(&FREQ::from_hz(hz))?;
 
         // get configured modulation setting.
-        let modulation = {
-            let reg = MDMCFG2(self.0.read_register(Config::MDMCFG2)?);
-
-            match reg.mod_format() {
-                0x00 => ModFormat::MOD_2FSK,
-                0x01 => ModFormat::MOD_GFSK,
-                0x03 => ModFormat::MOD_ASK_OOK,
-                0x04 => ModFormat::MOD_4FSK,
-                0x07 => ModFormat::MOD_MSK,
-                _ => return Err(Error::UnknownState),
-            }
-        };
+        let modulation =
+            self.radio.read::<MDMCFG2>()?.mod_format().map_err(|_| Error::UnknownState)?;
 
         // set 12dBm output power as the default
         // TODO: investigate using FREND0 and a real power table.
         self.update_pa_table(hz, modulation, 12)?;
+        self.update_rssi_offset()?;
         Ok(())
     }
 
     pub fn set_deviation(&mut self, deviation: u64) -> Result<(), Error<SpiE, GpioE>> {
         let (mantissa, exponent) = from_deviation(deviation);
-        self.0.write_register(
+        self.radio.write_register(
             Config::DEVIATN,
             DEVIATN::default().deviation_m(mantissa).deviation_e(exponent).bits(),
         )?;
@@ -86,35 +177,174 @@ where
     }
 
     pub fn set_data_rate(&mut self, baud: u64) -> Result<(), Error<SpiE, GpioE>> {
-        let (mantissa, exponent) = from_drate(baud);
-        self.0
-            .modify_register(Config::MDMCFG4, |r| MDMCFG4(r).modify().drate_e(exponent).bits())?;
-        self.0.write_register(Config::MDMCFG3, MDMCFG3::default().drate_m(mantissa).bits())?;
+        self.radio.write_composite(&DRATE::from_baud(baud))?;
+        self.update_rssi_offset()?;
+        Ok(())
+    }
+
+    /// Set the channel spacing, from 25 390 Hz (CHANSPC_E = 0, CHANSPC_M = 0) to 405 456 Hz
+    /// (CHANSPC_E = 3, CHANSPC_M = 255). Returns [`Error::InvalidArgument`] outside that range.
+    pub fn set_channel_spacing(&mut self, hz: u64) -> Result<(), Error<SpiE, GpioE>> {
+        if !(to_chanspc(0, 0)..=to_chanspc(255, 3)).contains(&hz) {
+            return Err(Error::InvalidArgument);
+        }
+        self.radio.write_composite(&CHANSPC::from_hz(hz))?;
         Ok(())
     }
 
     pub fn set_chanbw(&mut self, bandwidth: u64) -> Result<(), Error<SpiE, GpioE>> {
+        // The filter bandwidth ranges from 58 kHz (CHANBW_E = 3, CHANBW_M = 3) to 812 kHz.
+        if !(to_chanbw(3, 3)..=to_chanbw(0, 0)).contains(&bandwidth) {
+            return Err(Error::InvalidArgument);
+        }
         let (mantissa, exponent) = from_chanbw(bandwidth);
-        self.0.modify_register(Config::MDMCFG4, |r| {
-            MDMCFG4(r).modify().chanbw_m(mantissa).chanbw_e(exponent).bits()
-        })?;
+        self.radio.modify::<MDMCFG4>(|_, w| w.chanbw_m(mantissa).chanbw_e(exponent))?;
+        self.update_rssi_offset()?;
+        Ok(())
+    }
+
+    /// Configure the frequency offset compensation loop.
+    pub fn set_frequency_compensation(
+        &mut self,
+        foc: &FrequencyCompensation,
+    ) -> Result<(), Error<SpiE, GpioE>> {
+        self.radio.write_register(
+            Config::FOCCFG,
+            FOCCFG::default()
+                .foc_bs_cs_gate(foc.carrier_sense_gate as u8)
+                .foc_pre_k(foc.pre_k)
+                .foc_post_k(foc.post_k)
+                .foc_limit(foc.limit)
+                .bits(),
+        )?;
+        Ok(())
+    }
+
+    /// Configure the bit synchronization (clock recovery) loop.
+    pub fn set_bit_sync(&mut self, bs: &BitSync) -> Result<(), Error<SpiE, GpioE>> {
+        self.radio.write_register(
+            Config::BSCFG,
+            BSCFG::default()
+                .bs_pre_ki(bs.pre_ki)
+                .bs_pre_kp(bs.pre_kp)
+                .bs_post_ki(bs.post_ki)
+                .bs_post_kp(bs.post_kp)
+                .bs_limit(bs.limit)
+                .bits(),
+        )?;
         Ok(())
     }
 
     pub fn get_hw_info(&mut self) -> Result<(u8, u8), Error<SpiE, GpioE>> {
-        let partnum = self.0.read_register(Status::PARTNUM)?;
-        let version = self.0.read_register(Status::VERSION)?;
+        let partnum = self.radio.read_register(Status::PARTNUM)?;
+        let version = self.radio.read_register(Status::VERSION)?;
         Ok((partnum, version))
     }
 
+    /// Read all registers, for printing with the bitfields and derived settings decoded.
+    pub fn dump_registers(&mut self) -> Result<RegisterDump, Error<SpiE, GpioE>> {
+        Ok(self.radio.dump_registers()?)
+    }
+
+    /// Enable or disable caching of the configuration registers and the PA table, to save SPI
+    /// reads. The cache is filled from the chip when enabled.
+    pub fn set_register_cache(&mut self, enabled: bool) -> Result<(), Error<SpiE, GpioE>> {
+        self.radio.set_cache(enabled);
+        if enabled {
+            self.radio.sync()?;
+        }
+        Ok(())
+    }
+
+    /// Whether the register cache matches the chip, e.g. to detect a reset by brown-out.
+    pub fn verify_register_cache(&mut self) -> Result<bool, Error<SpiE, GpioE>> {
+        Ok(self.radio.verify()?)
+    }
+
     /// Received Signal Strength Indicator is an estimate of the signal power level in the chosen channel.
     pub fn get_rssi_dbm(&mut self) -> Result<i16, Error<SpiE, GpioE>> {
-        Ok(rssi_to_dbm(self.0.read_register(Status::RSSI)?))
+        let raw = self.radio.read_register(Status::RSSI)?;
+        Ok(self.rssi_dbm(raw))
+    }
+
+    /// Convert a raw RSSI value, such as the one appended to received packets, to dBm.
+    ///
+    /// Uses the typical offset for the configured data rate and frequency band, adjusted by the
+    /// calibration set with [`Cc1101::set_rssi_calibration`].
+    pub fn rssi_dbm(&self, raw: u8) -> i16 {
+        rssi_to_dbm(raw, self.rssi_offset) + self.rssi_calibration
+    }
+
+    /// Set a board specific calibration in dB, added to all RSSI values.
+    pub fn set_rssi_calibration(&mut self, offset_db: i16) {
+        self.rssi_calibration = offset_db;
+    }
+
+    // Called after every write of FREQ2/1/0 or MDMCFG4/3, and after a reset.
+    fn update_rssi_offset(&mut self) -> Result<(), Error<SpiE, GpioE>> {
+        let freq = self.radio.read_composite::<FREQ>()?;
+        let drate = self.radio.read_composite::<DRATE>()?;
+        self.rssi_offset = rssi_offset(drate.baud(), freq.hz());
+        Ok(())
+    }
+
+    /// Frequency offset between the carrier and the synthesizer, estimated by the demodulator
+    /// for the last received packet.
+    pub fn get_frequency_offset_hz(&mut self) -> Result<i32, Error<SpiE, GpioE>> {
+        let freqest = self.radio.read::<FREQEST>()?;
+        Ok(to_frequency_offset(freqest.freqoff_est()))
+    }
+
+    /// Add the current frequency offset estimate to the frequency trim in FSCTRL0, and return
+    /// the new trim. Should only be used after a packet was received with good CRC.
+    pub fn apply_frequency_correction(&mut self) -> Result<i8, Error<SpiE, GpioE>> {
+        let freqest = self.radio.read::<FREQEST>()?;
+        let trim = self.get_frequency_trim()?.saturating_add(freqest.freqoff_est() as i8);
+        self.set_frequency_trim(trim)?;
+        Ok(trim)
+    }
+
+    /// Enable or disable automatic frequency correction in [`Cc1101::receive`], applied after
+    /// every packet received with good CRC.
+    pub fn set_automatic_frequency_correction(&mut self, enabled: bool) {
+        self.afc = enabled;
+    }
+
+    /// Frequency trim in FSCTRL0, in steps of FXOSC/2^14 Hz (about 1.6 kHz).
+    pub fn get_frequency_trim(&mut self) -> Result<i8, Error<SpiE, GpioE>> {
+        let fsctrl0 = self.radio.read::<FSCTRL0>()?;
+        Ok(fsctrl0.freqoff() as i8)
+    }
+
+    /// Restore a frequency trim previously read with [`Cc1101::get_frequency_trim`].
+    pub fn set_frequency_trim(&mut self, trim: i8) -> Result<(), Error<SpiE, GpioE>> {
+        self.radio
+            .write_register(Config::FSCTRL0, FSCTRL0::default().freqoff(trim as u8).bits())?;
+        Ok(())
+    }
+
+    /// Sweep a range of frequencies or channels, measuring RSSI at each step.
+    ///
+    /// The channel filter bandwidth is set to `bandwidth` Hz, and `samples` RSSI readings are
+    /// taken at each step after dwelling long enough for RSSI to settle. The radio is left in
+    /// IDLE, tuned to the last frequency or channel of the range.
+    pub fn scan<'a, D>(
+        &'a mut self,
+        range: ScanRange,
+        bandwidth: u64,
+        samples: u8,
+        delay: &'a mut D,
+    ) -> Result<Scanner<'a, SPI, CS, D>, Error<SpiE, GpioE>>
+    where
+        D: DelayUs<u32>,
+    {
+        self.require(ChipVariant::can_receive)?;
+        Scanner::new(self, delay, range, bandwidth, samples)
     }
 
     /// The Link Quality Indicator metric of the current quality of the received signal.
     pub fn get_lqi(&mut self) -> Result<u8, Error<SpiE, GpioE>> {
-        let lqi = self.0.read_register(Status::LQI)?;
+        let lqi = self.radio.read_register(Status::LQI)?;
         Ok(lqi & !(1u8 << 7))
     }
 
@@ -128,11 +358,8 @@ where
             SyncMode::MatchPartialRepeated(word) => (SyncCheck::CHECK_30_32, word),
             SyncMode::MatchFull(word) => (SyncCheck::CHECK_16_16, word),
         };
-        self.0.modify_register(Config::MDMCFG2, |r| {
-            MDMCFG2(r).modify().sync_mode(mode.value()).bits()
-        })?;
-        self.0.write_register(Config::SYNC1, ((word >> 8) & 0xff) as u8)?;
-        self.0.write_register(Config::SYNC0, (word & 0xff) as u8)?;
+        self.radio.modify::<MDMCFG2>(|_, w| w.sync_mode(mode))?;
+        self.radio.write_composite(&SYNC(word))?;
         Ok(())
     }
 
@@ -140,6 +367,8 @@ where
     pub fn set_modulation(&mut self, format: Modulation) -> Result<(), Error<SpiE, GpioE>> {
         use lowlevel::types::ModFormat as MF;
 
+        self.require(|variant| variant.supports_modulation(&format))?;
+
         let value = match format {
             Modulation::BinaryFrequencyShiftKeying => MF::MOD_2FSK,
             Modulation::GaussianFrequencyShiftKeying => MF::MOD_GFSK,
@@ -147,17 +376,17 @@ where
             Modulation::FourFrequencyShiftKeying => MF::MOD_4FSK,
             Modulation::MinimumShiftKeying => MF::MOD_MSK,
         };
-        self.0.modify_register(Config::MDMCFG2, |r| {
-            MDMCFG2(r).modify().mod_format(value.value()).bits()
-        })?;
 
-        // to update power table we need the frequency.
+        let mdmcfg2 = self.radio.read::<MDMCFG2>()?;
+        if mdmcfg2.manchester_en() == 1 && !manchester_supported(value) {
+            return Err(Error::InvalidConfiguration);
+        }
+        self.radio.write_register(Config::MDMCFG2, mdmcfg2.modify().mod_format(value).bits())?;
 
-        let freq0 = self.0.read_register(Config::FREQ0)?;
-        let freq1 = self.0.read_register(Config::FREQ1)?;
-        let freq2 = self.0.read_register(Config::FREQ2)?;
+        // to update power table we need the frequency.
 
-        self.update_pa_table(to_frequency((freq0, freq1, freq2)), value, 12)?;
+        let freq = self.radio.read_composite::<FREQ>()?;
+        self.update_pa_table(freq.hz(), value, 12)?;
 
         Ok(())
     }
@@ -172,10 +401,8 @@ where
             AddressFilter::DeviceLowBroadcast(addr) => (AC::SELF_LOW_BROADCAST, addr),
             AddressFilter::DeviceHighLowBroadcast(addr) => (AC::SELF_HIGH_LOW_BROADCAST, addr),
         };
-        self.0.modify_register(Config::PKTCTRL1, |r| {
-            PKTCTRL1(r).modify().adr_chk(mode.value()).bits()
-        })?;
-        self.0.write_register(Config::ADDR, addr)?;
+        self.radio.modify::<PKTCTRL1>(|_, w| w.adr_chk(mode))?;
+        self.radio.write_register(Config::ADDR, addr)?;
         Ok(())
     }
 
@@ -188,10 +415,389 @@ where
             PacketLength::Variable(max_limit) => (LC::VARIABLE, max_limit),
             PacketLength::Infinite => (LC::INFINITE, PKTLEN::default().bits()),
         };
-        self.0.modify_register(Config::PKTCTRL0, |r| {
-            PKTCTRL0(r).modify().length_config(format.value()).bits()
+
+        // FEC is only supported with fixed packet length.
+        let mdmcfg1 = self.radio.read::<MDMCFG1>()?;
+        if mdmcfg1.fec_en() == 1 && format.value() != LC::FIXED.value() {
+            return Err(Error::InvalidConfiguration);
+        }
+
+        self.radio.modify::<PKTCTRL0>(|_, w| w.length_config(format))?;
+        self.radio.write_register(Config::PKTLEN, pktlen)?;
+        Ok(())
+    }
+
+    /// Configure preamble, CRC, whitening, FEC and Manchester options of the packet handler.
+    ///
+    /// The options are validated against the configured modulation and packet length, so those
+    /// should be configured first.
+    pub fn set_packet_handling(
+        &mut self,
+        packet: &PacketHandling,
+    ) -> Result<(), Error<SpiE, GpioE>> {
+        if packet.fec {
+            self.require(ChipVariant::supports_fec)?;
+        }
+
+        let mdmcfg2 = self.radio.read::<MDMCFG2>()?;
+        let pktctrl0 = self.radio.read::<PKTCTRL0>()?;
+        let mod_format = mdmcfg2.mod_format().map_err(|_| Error::UnknownState)?;
+        let length_config = pktctrl0.length_config().map_err(|_| Error::UnknownState)?;
+        if !packet.is_valid(mod_format, length_config) {
+            return Err(Error::InvalidConfiguration);
+        }
+
+        self.radio
+            .modify::<MDMCFG1>(|_, w| w.fec_en(packet.fec as u8).num_preamble(packet.preamble))?;
+        self.radio.write_register(
+            Config::MDMCFG2,
+            mdmcfg2.modify().manchester_en(packet.manchester as u8).bits(),
+        )?;
+        self.radio.modify::<PKTCTRL1>(|_, w| {
+            w.pqt(packet.preamble_quality_threshold).crc_autoflush(packet.crc_autoflush as u8)
+        })?;
+        self.radio.write_register(
+            Config::PKTCTRL0,
+            pktctrl0.modify().white_data(packet.whitening as u8).crc_en(packet.crc as u8).bits(),
+        )?;
+        Ok(())
+    }
+
+    /// Select how data is exchanged with the radio, and configure the GDO0 and GDO2 pins for it.
+    ///
+    /// Returns the role of each pin, GDO1 is shared with the SPI SO line and left alone.
+    pub fn set_data_mode(&mut self, mode: DataMode) -> Result<DataPins, Error<SpiE, GpioE>> {
+        let (format, gdo0, gdo2) = match mode {
+            DataMode::Packet => {
+                (PktFormat::NORMAL, GdoCfg::SYNC_WORD, GdoCfg::RX_FIFO_FILLED_END_OF_PKT)
+            }
+            DataMode::SynchronousSerial => {
+                (PktFormat::SYNCHRONOUS_SERIAL, GdoCfg::SERIAL_SYNC_DATA_OUT, GdoCfg::SERIAL_CLOCK)
+            }
+            DataMode::AsynchronousSerial => {
+                (PktFormat::ASYNCHRONOUS_SERIAL, GdoCfg::SERIAL_DATA_OUT, GdoCfg::SERIAL_DATA_OUT)
+            }
+            // Works as packet mode in RX.
+            DataMode::RandomTx => {
+                (PktFormat::RANDOM_TX, GdoCfg::SYNC_WORD, GdoCfg::RX_FIFO_FILLED_END_OF_PKT)
+            }
+        };
+
+        self.radio.modify::<PKTCTRL0>(|_, w| w.pkt_format(format))?;
+        self.radio.write_register(Config::IOCFG2, IOCFG2::default().gdo2_cfg(gdo2).bits())?;
+        self.radio.write_register(Config::IOCFG0, IOCFG0::default().gdo0_cfg(gdo0).bits())?;
+
+        Ok(DataPins {
+            gdo0: GdoRole::from(gdo0),
+            gdo2: GdoRole::from(gdo2),
+        })
+    }
+
+    /// Configure when the clear channel assessment (CCA) signal is asserted.
+    pub fn set_cca_mode(&mut self, mode: CcaMode) -> Result<(), Error<SpiE, GpioE>> {
+        self.radio.modify::<MCSM1>(|_, w| w.cca_mode(mode))?;
+        Ok(())
+    }
+
+    /// Configure carrier sense thresholds.
+    ///
+    /// The absolute threshold is given in dB relative to the AGC magnitude target,
+    /// from -7 to +7 dB, or `None` to disable it.
+    pub fn set_carrier_sense_threshold(
+        &mut self,
+        absolute_db: Option<i8>,
+        relative: CarrierSenseRelThr,
+    ) -> Result<(), Error<SpiE, GpioE>> {
+        let absolute = match absolute_db {
+            // 0b1000 (-8) disables the absolute threshold.
+            None => 0b1000,
+            Some(db) if (-7..=7).contains(&db) => (db as u8) & 0x0F,
+            Some(_) => return Err(Error::InvalidArgument),
+        };
+        self.radio.modify::<AGCCTRL1>(|_, w| {
+            w.carrier_sense_abs_thr(absolute).carrier_sense_rel_thr(relative)
         })?;
-        self.0.write_register(Config::PKTLEN, pktlen)?;
+        Ok(())
+    }
+
+    /// Configure the automatic gain control.
+    pub fn set_agc(&mut self, agc: &AgcConfig) -> Result<(), Error<SpiE, GpioE>> {
+        self.radio.write_register(
+            Config::AGCCTRL2,
+            AGCCTRL2::default()
+                .max_dvga_gain(agc.max_dvga_gain)
+                .max_lna_gain(agc.max_lna_gain)
+                .magn_target(agc.magn_target)
+                .bits(),
+        )?;
+        // Keep the carrier sense thresholds.
+        self.radio.modify::<AGCCTRL1>(|_, w| w.agc_lna_priority(agc.lna_priority))?;
+        self.radio.write_register(
+            Config::AGCCTRL0,
+            AGCCTRL0::default()
+                .hyst_level(agc.hyst_level)
+                .wait_time(agc.wait_time)
+                .agc_freeze(agc.freeze)
+                .filter_length(agc.filter_length)
+                .bits(),
+        )?;
+        Ok(())
+    }
+
+    /// Whether the channel is clear, according to the configured CCA mode.
+    pub fn is_channel_clear(&mut self) -> Result<bool, Error<SpiE, GpioE>> {
+        let pktstatus = self.radio.read::<PKTSTATUS>()?;
+        Ok(pktstatus.cca() == 1)
+    }
+
+    /// Whether carrier sense is asserted, according to the configured thresholds.
+    pub fn is_carrier_sensed(&mut self) -> Result<bool, Error<SpiE, GpioE>> {
+        let pktstatus = self.radio.read::<PKTSTATUS>()?;
+        Ok(pktstatus.cs() == 1)
+    }
+
+    /// Transmit a packet once the channel is clear.
+    ///
+    /// The payload is written to the TX FIFO as is, so it must include the length and address
+    /// bytes if the packet configuration expects them. The radio listens in RX before each
+    /// attempt, and only enters TX if the CCA signal is asserted, otherwise it backs off for a
+    /// random number of slots. The CCA mode must be configured with [`Cc1101::set_cca_mode`].
+    pub fn transmit_lbt<D>(
+        &mut self,
+        payload: &[u8],
+        lbt: &ListenBeforeTalk,
+        delay: &mut D,
+    ) -> Result<(), Error<SpiE, GpioE>>
+    where
+        D: DelayUs<u32>,
+    {
+        self.require(ChipVariant::can_transmit)?;
+
+        self.set_radio_mode(RadioMode::Idle)?;
+        self.radio.write_strobe(Command::SFTX)?;
+        self.radio.write_register_burst(Command::FIFO, payload)?;
+
+        let mut backoff = Backoff::new();
+        for attempt in 0..lbt.attempts {
+            self.set_radio_mode(RadioMode::Receive)?;
+            delay.delay_us(lbt.listen_us);
+
+            // The chip stays in RX if the channel is not clear. The RX to TX transition takes
+            // a moment to show in MARCSTATE.
+            self.radio.write_strobe(Command::STX)?;
+            for _ in 0..TX_TURNAROUND_POLLS {
+                let marcstate = self.radio.read::<MARCSTATE>()?;
+                if marcstate.marc_state() != Ok(MachineState::RX) {
+                    self.await_tx_off()?;
+                    return Ok(());
+                }
+                delay.delay_us(TX_TURNAROUND_POLL_US);
+            }
+
+            // The least significant bits of RSSI are mostly noise.
+            backoff.mix(self.radio.read_register(Status::RSSI)?);
+            delay.delay_us(backoff.delay_us(lbt, attempt));
+        }
+
+        self.set_radio_mode(RadioMode::Idle)?;
+        self.radio.write_strobe(Command::SFTX)?;
+        Err(Error::ChannelBusy)
+    }
+
+    /// Transmit a request and wait for the reply.
+    ///
+    /// TXOFF_MODE is temporarily set to RX so the radio turns around on its own after sending,
+    /// without going through IDLE and calibration. The payload is written to the TX FIFO as is,
+    /// see [`Cc1101::transmit_lbt`]. The reply is read as with [`Cc1101::receive`], or
+    /// [`Error::Timeout`] is returned if nothing arrives within `rx_timeout_us`.
+    pub fn transmit_then_receive<D>(
+        &mut self,
+        payload: &[u8],
+        rx_timeout_us: u32,
+        delay: &mut D,
+        addr: &mut u8,
+        buf: &mut [u8],
+    ) -> Result<u8, Error<SpiE, GpioE>>
+    where
+        D: DelayUs<u32>,
+    {
+        self.require(ChipVariant::can_transmit)?;
+        self.require(ChipVariant::can_receive)?;
+
+        // The previous states are restored on every exit, also after an error.
+        let (rxoff_mode, txoff_mode) = (self.rxoff_mode, self.txoff_mode);
+        let result = self.turnaround(payload, rx_timeout_us, delay, addr, buf);
+        let restore_tx = self.set_tx_off_mode(txoff_mode);
+        let restore_rx = self.set_rx_off_mode(rxoff_mode);
+
+        let length = result?;
+        restore_tx?;
+        restore_rx?;
+        Ok(length)
+    }
+
+    // Sends the payload and reads the reply, with TXOFF_MODE set to RX.
+    fn turnaround<D>(
+        &mut self,
+        payload: &[u8],
+        rx_timeout_us: u32,
+        delay: &mut D,
+        addr: &mut u8,
+        buf: &mut [u8],
+    ) -> Result<u8, Error<SpiE, GpioE>>
+    where
+        D: DelayUs<u32>,
+    {
+        const POLL_US: u32 = 100;
+
+        self.set_tx_off_mode(TxOffMode::RX)?;
+        self.set_rx_off_mode(RxOffMode::IDLE)?;
+
+        self.set_radio_mode(RadioMode::Idle)?;
+        self.radio.write_strobe(Command::SFTX)?;
+        self.radio.write_strobe(Command::SFRX)?;
+        self.radio.write_register_burst(Command::FIFO, payload)?;
+        self.radio.write_strobe(Command::STX)?;
+        self.await_tx_off()?;
+
+        let mut waited = 0;
+        loop {
+            let rxbytes = self.radio.read::<RXBYTES>()?;
+            if rxbytes.num_rxbytes() > 0 || rxbytes.rxfifo_overflow() == 1 {
+                return self.receive(addr, buf);
+            }
+            if waited >= rx_timeout_us {
+                self.set_radio_mode(RadioMode::Idle)?;
+                self.radio.write_strobe(Command::SFRX)?;
+                return Err(Error::Timeout);
+            }
+            delay.delay_us(POLL_US);
+            waited = waited.saturating_add(POLL_US);
+        }
+    }
+
+    /// Send an OOK/ASK signal by driving `pin`, connected to GDO0, in asynchronous serial mode.
+    ///
+    /// The waveform is sent `repeats` times. Modulation should be set to
+    /// [`Modulation::OnOffKeying`] first. Afterwards the radio is left in IDLE, with the previous
+    /// data mode and GDO configuration restored.
+    pub fn transmit_ook<P, D>(
+        &mut self,
+        pin: &mut P,
+        waveform: &Waveform,
+        repeats: u16,
+        delay: &mut D,
+    ) -> Result<(), Error<SpiE, GpioE>>
+    where
+        P: OutputPin<Error = GpioE>,
+        D: DelayUs<u32>,
+    {
+        self.require(ChipVariant::can_transmit)?;
+
+        let pktctrl0 = self.radio.read_register(Config::PKTCTRL0)?;
+        let iocfg0 = self.radio.read_register(Config::IOCFG0)?;
+        let iocfg2 = self.radio.read_register(Config::IOCFG2)?;
+
+        // The packet configuration is restored on every exit, also after an error, and each step
+        // is tried even if the previous one failed. The first error is returned.
+        let result = self.send_ook(pin, waveform, repeats, delay);
+        let idle = self.set_radio_mode(RadioMode::Idle);
+        let packet_mode =
+            [(Config::PKTCTRL0, pktctrl0), (Config::IOCFG0, iocfg0), (Config::IOCFG2, iocfg2)];
+        let mut restore = idle;
+        for &(reg, value) in packet_mode.iter() {
+            let write = self.radio.write_register(reg, value);
+            restore = restore.and(write.map_err(Error::from));
+        }
+        result.and(restore)
+    }
+
+    // Sends the waveform in asynchronous serial mode.
+    fn send_ook<P, D>(
+        &mut self,
+        pin: &mut P,
+        waveform: &Waveform,
+        repeats: u16,
+        delay: &mut D,
+    ) -> Result<(), Error<SpiE, GpioE>>
+    where
+        P: OutputPin<Error = GpioE>,
+        D: DelayUs<u32>,
+    {
+        pin.set_low().map_err(Error::Gpio)?;
+        self.set_radio_mode(RadioMode::Idle)?;
+        self.set_data_mode(DataMode::AsynchronousSerial)?;
+        self.set_radio_mode(RadioMode::Transmit)?;
+
+        (0..repeats).try_for_each(|_| waveform.send(pin, delay)).map_err(Error::Gpio)
+    }
+
+    /// Apply the preset and frequency of a Flipper Zero `.sub` file, and send its `RAW_Data`
+    /// with [`Cc1101::transmit_ook`].
+    ///
+    /// Returns [`Error::InvalidArgument`] for firmware presets without known register values,
+    /// and for files without `RAW_Data`, such as key files.
+    #[cfg(feature = "alloc")]
+    pub fn transmit_sub_file<P, D>(
+        &mut self,
+        file: &flipper::SubFile,
+        pin: &mut P,
+        repeats: u16,
+        delay: &mut D,
+    ) -> Result<(), Error<SpiE, GpioE>>
+    where
+        P: OutputPin<Error = GpioE>,
+        D: DelayUs<u32>,
+    {
+        if file.raw_data.is_empty() {
+            return Err(Error::InvalidArgument);
+        }
+        let settings = file.settings().ok_or(Error::InvalidArgument)?;
+        self.apply_settings(&settings)?;
+
+        let pulses: alloc::vec::Vec<ook::Pulse> = file.pulses().collect();
+        self.transmit_ook(pin, &Waveform::Pulses(&pulses), repeats, delay)
+    }
+
+    /// Configure what state the radio enters after a packet has been received.
+    pub fn set_rx_off_mode(&mut self, mode: RxOffMode) -> Result<(), Error<SpiE, GpioE>> {
+        self.radio.modify::<MCSM1>(|_, w| w.rxoff_mode(mode))?;
+        self.rxoff_mode = mode;
+        Ok(())
+    }
+
+    /// Configure what state the radio enters after a packet has been sent.
+    pub fn set_tx_off_mode(&mut self, mode: TxOffMode) -> Result<(), Error<SpiE, GpioE>> {
+        self.radio.modify::<MCSM1>(|_, w| w.txoff_mode(mode))?;
+        self.txoff_mode = mode;
+        Ok(())
+    }
+
+    /// Configure what state transitions result in frequency synthesizer calibration.
+    pub fn set_auto_calibration(
+        &mut self,
+        mode: AutoCalibration,
+    ) -> Result<(), Error<SpiE, GpioE>> {
+        self.radio.modify::<MCSM0>(|_, w| w.fs_autocal(mode))?;
+        Ok(())
+    }
+
+    /// Configure how long to wait for the crystal oscillator to stabilize.
+    pub fn set_po_timeout(&mut self, timeout: PoTimeout) -> Result<(), Error<SpiE, GpioE>> {
+        self.radio.modify::<MCSM0>(|_, w| w.po_timeout(timeout))?;
+        Ok(())
+    }
+
+    /// Configure when RX is terminated.
+    pub fn set_rx_termination(&mut self, rx: &RxTermination) -> Result<(), Error<SpiE, GpioE>> {
+        self.radio.write_register(
+            Config::MCSM2,
+            MCSM2::default()
+                .rx_time_rssi(rx.on_rssi as u8)
+                .rx_time_qual(rx.qualifier)
+                .rx_time(rx.timeout)
+                .bits(),
+        )?;
         Ok(())
     }
 
@@ -199,19 +805,21 @@ where
     pub fn set_radio_mode(&mut self, radio_mode: RadioMode) -> Result<(), Error<SpiE, GpioE>> {
         let target = match radio_mode {
             RadioMode::Receive => {
+                self.require(ChipVariant::can_receive)?;
                 self.set_radio_mode(RadioMode::Idle)?;
                 self.await_machine_state(MachineState::IDLE)?;
-                self.0.write_strobe(Command::SRX)?;
+                self.radio.write_strobe(Command::SRX)?;
                 MachineState::RX
             }
             RadioMode::Transmit => {
+                self.require(ChipVariant::can_transmit)?;
                 self.set_radio_mode(RadioMode::Idle)?;
                 self.await_machine_state(MachineState::IDLE)?;
-                self.0.write_strobe(Command::STX)?;
+                self.radio.write_strobe(Command::STX)?;
                 MachineState::TX
             }
             RadioMode::Idle => {
-                self.0.write_strobe(Command::SIDLE)?;
+                self.radio.write_strobe(Command::SIDLE)?;
                 self.await_machine_state(MachineState::IDLE)?;
                 MachineState::IDLE
             }
@@ -241,69 +849,170 @@ where
             _ => pa_table[0] = pa_level,
         }
 
-        self.0.write_register_burst(Command::PATABLE, &pa_table)?;
+        self.radio.write_register_burst(Command::PATABLE, &pa_table)?;
 
         match modulation {
             ModFormat::MOD_ASK_OOK => {
-                self.0.write_register(Config::FREND0, FREND0::default().pa_power(1).bits())?;
+                self.radio.write_register(Config::FREND0, FREND0::default().pa_power(1).bits())?;
             }
             _ => {
-                self.0.write_register(Config::FREND0, FREND0::default().pa_power(0).bits())?;
+                self.radio.write_register(Config::FREND0, FREND0::default().pa_power(0).bits())?;
             }
         }
 
         Ok(())
     }
 
+    /// Write the register values, and the PA table if set.
+    pub fn apply_settings(
+        &mut self,
+        settings: &RegisterSettings,
+    ) -> Result<(), Error<SpiE, GpioE>> {
+        self.write_settings(settings)?;
+        Ok(())
+    }
+
+    /// Read the values of all configuration registers and the PA table.
+    pub fn read_settings(&mut self) -> Result<RegisterSettings, Error<SpiE, GpioE>> {
+        let mut config = [0; Config::ALL.len()];
+        self.radio.read_register_burst(Config::IOCFG2, &mut config)?;
+        let mut settings = RegisterSettings::new().with_pa_table(self.radio.read_pa_table()?);
+        for (&reg, &value) in Config::ALL.iter().zip(config.iter()) {
+            settings.set(reg, value);
+        }
+        Ok(settings)
+    }
+
+    /// Write only the register values, and the PA table, that differ from the chip. Returns the
+    /// number of bytes written, not counting the address bytes.
+    ///
+    /// The current values are read with [`Cc1101::read_settings`], which takes a 55 byte SPI
+    /// read unless the register cache is enabled. Use [`Cc1101::update_settings_from`] with a
+    /// known snapshot to skip it.
+    pub fn update_settings(
+        &mut self,
+        target: &RegisterSettings,
+    ) -> Result<usize, Error<SpiE, GpioE>> {
+        let current = self.read_settings()?;
+        self.update_settings_from(&current, target)
+    }
+
+    /// Write only the register values, and the PA table, that differ from `current`, e.g. the
+    /// settings last applied. Registers without a value in `current` are written. Returns the
+    /// number of bytes written, not counting the address bytes.
+    pub fn update_settings_from(
+        &mut self,
+        current: &RegisterSettings,
+        target: &RegisterSettings,
+    ) -> Result<usize, Error<SpiE, GpioE>> {
+        let mut diff = current.diff(target);
+        diff.fill_gaps(current);
+        self.write_settings(&diff)
+    }
+
+    // Writes each run of consecutive registers in one burst, and returns the bytes written.
+    fn write_settings(&mut self, settings: &RegisterSettings) -> Result<usize, Error<SpiE, GpioE>> {
+        let mut written = 0;
+        for (start, len) in settings.runs() {
+            let mut bytes = [0; Config::ALL.len()];
+            let bytes = &mut bytes[..len];
+            for (byte, &reg) in bytes.iter_mut().zip(&Config::ALL[start as usize..]) {
+                *byte = settings.get(reg).unwrap_or_default();
+            }
+            self.radio.write_register_burst(start, bytes)?;
+            written += len;
+        }
+        if let Some(pa_table) = settings.pa_table() {
+            self.radio.write_register_burst(Command::PATABLE, &pa_table)?;
+            written += pa_table.len();
+        }
+
+        if let Some(mcsm1) = settings.get(Config::MCSM1).map(MCSM1) {
+            if let Ok(mode) = mcsm1.rxoff_mode() {
+                self.rxoff_mode = mode;
+            }
+            if let Ok(mode) = mcsm1.txoff_mode() {
+                self.txoff_mode = mode;
+            }
+        }
+        self.update_rssi_offset()?;
+        Ok(written)
+    }
+
     /// Configure some default settings, to be removed in the future.
     pub fn set_defaults(&mut self) -> Result<(), Error<SpiE, GpioE>> {
-        self.0.write_strobe(Command::SRES)?;
+        self.radio.write_strobe(Command::SRES)?;
+        self.update_rssi_offset()?;
+        self.rxoff_mode = RxOffMode::IDLE;
+        self.txoff_mode = TxOffMode::IDLE;
 
-        // pkt_format 3 enables asynchronous (compatibility) mode - GDO pins can be used for RX and TX if iocfg is configured.
-        self.0.write_register(
-            Config::PKTCTRL0,
-            PKTCTRL0::default().white_data(0).pkt_format(3).bits(),
-        )?;
+        self.set_packet_handling(&PacketHandling::default())?;
+
+        // Asynchronous serial mode, GDO0 is used for TX in TX mode. See section 27.1 for more info.
+        self.set_data_mode(DataMode::AsynchronousSerial)?;
 
-        // Enable input/output on GDO0 and GDO2.
-        // GDO0 is used for TX in TX mode. See section 27.1 for more info.
-        self.0.write_register(Config::IOCFG2, 0x0D)?;
-        self.0.write_register(Config::IOCFG0, 0x0D)?;
         //FREND0 should be updated when modulation changes and perhaps when PA table changes.
-        self.0.write_register(Config::FREND0, FREND0::default().bits())?;
+        self.radio.write_register(Config::FREND0, FREND0::default().bits())?;
 
-        self.0.write_register(
+        self.radio.write_register(
             Config::FSCTRL1,
             FSCTRL1::default().freq_if(0x08).bits(), // f_if = (f_osc / 2^10) * FREQ_IF
         )?;
 
-        self.0.write_register(Config::MDMCFG2, MDMCFG2::default().dem_dcfilt_off(1).bits())?;
+        self.radio.write_register(Config::MDMCFG2, MDMCFG2::default().dem_dcfilt_off(1).bits())?;
 
-        self.0.write_register(
+        self.radio.write_register(
             Config::MCSM0,
-            MCSM0::default().fs_autocal(AutoCalibration::FROM_IDLE.value()).bits(),
+            MCSM0::default().fs_autocal(AutoCalibration::FROM_IDLE).bits(),
         )?;
 
-        self.0.write_register(Config::AGCCTRL2, AGCCTRL2::default().max_lna_gain(0x04).bits())?;
+        self.radio.write_register(
+            Config::AGCCTRL2,
+            AGCCTRL2::default().max_lna_gain(MaxLnaGain::MINUS_9_2_DB).bits(),
+        )?;
 
         Ok(())
     }
 
     fn await_machine_state(&mut self, target: MachineState) -> Result<(), Error<SpiE, GpioE>> {
         loop {
-            let marcstate = MARCSTATE(self.0.read_register(Status::MARCSTATE)?);
-            if target.value() == marcstate.marc_state() {
+            let marcstate = self.radio.read::<MARCSTATE>()?;
+            if marcstate.marc_state() == Ok(target) {
                 break;
             }
         }
         Ok(())
     }
 
+    /// Wait for the radio to leave TX, into the state configured with
+    /// [`Cc1101::set_tx_off_mode`].
+    fn await_tx_off(&mut self) -> Result<(), Error<SpiE, GpioE>> {
+        match self.txoff_mode {
+            TxOffMode::IDLE => self.await_machine_state(MachineState::IDLE),
+            TxOffMode::FSTXON => self.await_machine_state(MachineState::FSTXON),
+            // Sending preamble until there is more data in the TX FIFO.
+            TxOffMode::STAY_IN_TX => Ok(()),
+            TxOffMode::RX => self.await_machine_state(MachineState::RX),
+        }
+    }
+
+    /// Wait for the radio to leave RX, into the state configured with
+    /// [`Cc1101::set_rx_off_mode`].
+    fn await_rx_off(&mut self) -> Result<(), Error<SpiE, GpioE>> {
+        match self.rxoff_mode {
+            RxOffMode::IDLE => self.await_machine_state(MachineState::IDLE),
+            RxOffMode::FSTXON => self.await_machine_state(MachineState::FSTXON),
+            // The TX FIFO is sent, and the radio continues according to TXOFF_MODE.
+            RxOffMode::TX => Ok(()),
+            RxOffMode::STAY_IN_RX => self.await_machine_state(MachineState::RX),
+        }
+    }
+
     fn rx_bytes_available(&mut self) -> Result<u8, Error<SpiE, GpioE>> {
         let mut last = 0;
 
         loop {
-            let rxbytes = RXBYTES(self.0.read_register(Status::RXBYTES)?);
+            let rxbytes = self.radio.read::<RXBYTES>()?;
             if rxbytes.rxfifo_overflow() == 1 {
                 return Err(Error::RxOverflow);
             }
@@ -318,25 +1027,85 @@ where
         Ok(last)
     }
 
-    // Should also be able to configure MCSM1.RXOFF_MODE to declare what state
-    // to enter after fully receiving a packet.
-    // Possible targets: IDLE, FSTON, TX, RX
+    // Reads the payload into `buf`, after the length and address bytes as configured in
+    // PKTCTRL0.LENGTH_CONFIG and PKTCTRL1.ADR_CHK, and the status bytes appended with
+    // PKTCTRL1.APPEND_STATUS. Returns the payload length and whether the CRC matched.
+    fn read_packet(
+        &mut self,
+        nbytes: u8,
+        addr: &mut u8,
+        buf: &mut [u8],
+    ) -> Result<(u8, bool), Error<SpiE, GpioE>> {
+        let length_config =
+            self.radio.read::<PKTCTRL0>()?.length_config().map_err(|_| Error::UnknownState)?;
+        let pktctrl1 = self.radio.read::<PKTCTRL1>()?;
+        let address_check = pktctrl1.adr_chk() != Ok(AddressCheck::DISABLED);
+
+        let mut length = match length_config {
+            LengthConfig::FIXED => self.radio.read_register(Config::PKTLEN)?,
+            LengthConfig::VARIABLE => {
+                let mut length = [0u8];
+                self.radio.read_fifo_raw(&mut length)?;
+                length[0]
+            }
+            LengthConfig::INFINITE => nbytes,
+        };
+        // The address byte is counted in the packet length.
+        if address_check && length > 0 {
+            let mut address = [0u8];
+            self.radio.read_fifo_raw(&mut address)?;
+            *addr = address[0];
+            length -= 1;
+        }
+
+        let mut length = usize::from(length);
+        if length_config == LengthConfig::INFINITE {
+            length = length.min(buf.len());
+        }
+        let payload = buf.get_mut(..length).ok_or(Error::BufferTooSmall)?;
+        self.radio.read_fifo_raw(payload)?;
+
+        // RSSI, and LQI with CRC_OK in the most significant bit. Infinite packets have no end
+        // to append them to.
+        let lqi = if pktctrl1.append_status() == 1 && length_config != LengthConfig::INFINITE {
+            let mut status = [0u8; 2];
+            self.radio.read_fifo_raw(&mut status)?;
+            status[1]
+        } else {
+            self.radio.read_register(Status::LQI)?
+        };
+        Ok((length as u8, lqi >> 7 == 1))
+    }
+
+    /// Read a received packet into `buf`, and wait for the radio to enter the state configured
+    /// with [`Cc1101::set_rx_off_mode`]. Returns the payload length.
+    ///
+    /// The length and address bytes are read as configured with [`Cc1101::set_packet_length`]
+    /// and [`Cc1101::set_address_filter`]. `addr` is only set when the address is checked. In
+    /// infinite packet length mode, the bytes in the RX FIFO are read as far as `buf` allows.
     pub fn receive(&mut self, addr: &mut u8, buf: &mut [u8]) -> Result<u8, Error<SpiE, GpioE>> {
-        match self.rx_bytes_available() {
-            Ok(_nbytes) => {
-                let mut length = 0u8;
-                self.0.read_fifo(addr, &mut length, buf)?;
-                let lqi = self.0.read_register(Status::LQI)?;
-                self.await_machine_state(MachineState::IDLE)?;
-                self.0.write_strobe(Command::SFRX)?;
-                if (lqi >> 7) != 1 {
+        self.require(ChipVariant::can_receive)?;
+        match self.rx_bytes_available().and_then(|nbytes| self.read_packet(nbytes, addr, buf)) {
+            Ok((length, crc_ok)) => {
+                self.await_rx_off()?;
+                // The RX FIFO can only be flushed in IDLE. Otherwise the whole packet has been
+                // read, and the next packet starts at the head of the FIFO.
+                if let RxOffMode::IDLE = self.rxoff_mode {
+                    self.radio.write_strobe(Command::SFRX)?;
+                }
+                if !crc_ok {
                     Err(Error::CrcMismatch)
                 } else {
+                    if self.afc {
+                        self.apply_frequency_correction()?;
+                    }
                     Ok(length)
                 }
             }
             Err(err) => {
-                self.0.write_strobe(Command::SFRX)?;
+                // SFRX is only allowed in IDLE and RXFIFO_OVERFLOW.
+                self.set_radio_mode(RadioMode::Idle)?;
+                self.radio.write_strobe(Command::SFRX)?;
                 Err(err)
             }
         }
@@ -367,6 +1136,51 @@ pub enum PacketLength {
     Infinite,
 }
 
+/// Data path between the MCU and the radio.
+pub enum DataMode {
+    /// Packets are written to and read from the FIFOs over SPI.
+    Packet,
+    /// Bits are clocked in and out on GDO0, with the radio providing the clock on GDO2.
+    SynchronousSerial,
+    /// Bits are driven on GDO0 in TX and sampled from GDO0 or GDO2 in RX, without a clock.
+    AsynchronousSerial,
+    /// PN9 random data is transmitted, for test. Works as packet mode in RX.
+    RandomTx,
+}
+
+/// Role of the GDO pins in a [`DataMode`].
+pub struct DataPins {
+    pub gdo0: GdoRole,
+    pub gdo2: GdoRole,
+}
+
+/// Role of a GDO pin.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GdoRole {
+    /// Asserts when a sync word has been sent or received, de-asserts at the end of the packet.
+    SyncWord,
+    /// Asserts when the RX FIFO is filled above the threshold or the end of packet is reached.
+    RxFifo,
+    /// Serial clock output, data is set up on the falling edge and sampled on the rising edge.
+    SerialClock,
+    /// Serial data input in TX, serial data output in RX.
+    SerialData,
+    /// Configured for something other than data transfer.
+    Other(u8),
+}
+
+impl From<GdoCfg> for GdoRole {
+    fn from(cfg: GdoCfg) -> Self {
+        match cfg {
+            GdoCfg::SYNC_WORD => GdoRole::SyncWord,
+            GdoCfg::RX_FIFO_FILLED_END_OF_PKT => GdoRole::RxFifo,
+            GdoCfg::SERIAL_CLOCK => GdoRole::SerialClock,
+            GdoCfg::SERIAL_SYNC_DATA_OUT | GdoCfg::SERIAL_DATA_OUT => GdoRole::SerialData,
+            other => GdoRole::Other(other.value()),
+        }
+    }
+}
+
 /// Address check configuration.
 pub enum AddressFilter {
     /// No address check.
@@ -386,6 +1200,16 @@ pub enum RadioMode {
     Idle,
 }
 
+/// RX termination configuration.
+pub struct RxTermination {
+    /// Terminate RX directly when the RSSI is below threshold (carrier sense not asserted).
+    pub on_rssi: bool,
+    /// What has to be found before the timeout expires to keep receiving.
+    pub qualifier: RxTimeQual,
+    /// Timeout for sync word search.
+    pub timeout: RxTime,
+}
+
 /// Sync word configuration.
 pub enum SyncMode {
     /// No sync word.
@@ -397,3 +1221,430 @@ pub enum SyncMode {
     /// Match 16 of 16 bits of given sync word.
     MatchFull(u16),
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::lowlevel::sim::{self, Chip, Delay, Fault, Pin};
+    use crate::*;
+    use core::cell::RefCell;
+
+    const ADDRESS: u8 = 0x42;
+    const PAYLOAD: [u8; 4] = [0x01, 0x02, 0x03, 0x04];
+
+    fn rx_chip(length_config: LengthConfig, address_check: AddressCheck) -> RefCell<Chip> {
+        let mut chip = Chip::new();
+        chip.config[Config::PKTCTRL0 as usize] =
+            PKTCTRL0::default().length_config(length_config).bits();
+        chip.config[Config::PKTCTRL1 as usize] = PKTCTRL1::default().adr_chk(address_check).bits();
+        chip.status[Status::LQI as usize - 0x30] = 0x80;
+        chip.status[Status::MARCSTATE as usize - 0x30] = MachineState::IDLE.value();
+
+        let address = address_check != AddressCheck::DISABLED;
+        let length = PAYLOAD.len() as u8 + address as u8;
+        if length_config == LengthConfig::FIXED {
+            chip.config[Config::PKTLEN as usize] = length;
+        }
+        let mut packet = [0; 8];
+        let mut len = 0;
+        if length_config == LengthConfig::VARIABLE {
+            packet[len] = length;
+            len += 1;
+        }
+        if address {
+            packet[len] = ADDRESS;
+            len += 1;
+        }
+        packet[len..][..PAYLOAD.len()].copy_from_slice(&PAYLOAD);
+        chip.receive_packet(&packet[..len + PAYLOAD.len()]);
+        // Back to back, read when staying in RX. Infinite packets never end.
+        if length_config != LengthConfig::INFINITE {
+            chip.receive_packet(&packet[..len + PAYLOAD.len()]);
+        }
+        RefCell::new(chip)
+    }
+
+    #[test]
+    fn test_receive() {
+        let length_configs = [LengthConfig::FIXED, LengthConfig::VARIABLE, LengthConfig::INFINITE];
+        let address_checks = [
+            AddressCheck::DISABLED,
+            AddressCheck::SELF,
+            AddressCheck::SELF_LOW_BROADCAST,
+            AddressCheck::SELF_HIGH_LOW_BROADCAST,
+        ];
+        for &length_config in length_configs.iter() {
+            for &address_check in address_checks.iter() {
+                let chip = rx_chip(length_config, address_check);
+                let mut radio = sim::cc1101(&chip);
+
+                let mut addr = 0;
+                let mut buf = [0; 8];
+                let length = radio.receive(&mut addr, &mut buf).unwrap();
+                assert_eq!(&buf[..usize::from(length)], &PAYLOAD, "{:?}", length_config);
+                if address_check == AddressCheck::DISABLED {
+                    assert_eq!(addr, 0);
+                } else {
+                    assert_eq!(addr, ADDRESS);
+                }
+                assert_eq!(chip.borrow().rx_bytes(), 0);
+
+                // Both packets are read when staying in RX, except in infinite packet length
+                // mode where everything is one packet.
+                if length_config == LengthConfig::INFINITE {
+                    continue;
+                }
+                let chip = rx_chip(length_config, address_check);
+                chip.borrow_mut().set_state(MachineState::RX);
+                let mut radio = sim::cc1101(&chip);
+                radio.set_rx_off_mode(RxOffMode::STAY_IN_RX).unwrap();
+                for _ in 0..2 {
+                    let mut addr = 0;
+                    let length = radio.receive(&mut addr, &mut buf).unwrap();
+                    assert_eq!(&buf[..usize::from(length)], &PAYLOAD, "{:?}", length_config);
+                    assert_eq!(addr != 0, address_check != AddressCheck::DISABLED);
+                }
+                assert_eq!(chip.borrow().rx_bytes(), 0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_receive_overflow() {
+        let chip = rx_chip(LengthConfig::VARIABLE, AddressCheck::SELF);
+        let mut radio = sim::cc1101(&chip);
+        let mut buf = [0; 2];
+        assert!(matches!(radio.receive(&mut 0, &mut buf), Err(Error::BufferTooSmall)));
+        assert_eq!(chip.borrow().rx_bytes(), 0);
+
+        // Infinite packet length mode reads as far as the buffer allows.
+        let chip = rx_chip(LengthConfig::INFINITE, AddressCheck::DISABLED);
+        let mut radio = sim::cc1101(&chip);
+        assert_eq!(radio.receive(&mut 0, &mut buf).unwrap(), 2);
+        assert_eq!(buf, PAYLOAD[..2]);
+    }
+
+    #[test]
+    fn test_transmit_lbt() {
+        let chip = RefCell::new(Chip::new());
+        let mut radio = sim::cc1101(&chip);
+        let lbt = ListenBeforeTalk::default();
+        radio.transmit_lbt(&[0x01, 0x02], &lbt, &mut Delay).unwrap();
+        assert_eq!(radio.radio.read::<MARCSTATE>().unwrap().marc_state(), Ok(MachineState::IDLE));
+
+        chip.borrow_mut().channel_busy = true;
+        let transactions = chip.borrow().transactions;
+        assert!(matches!(
+            radio.transmit_lbt(&[0x01, 0x02], &lbt, &mut Delay),
+            Err(Error::ChannelBusy)
+        ));
+        // Every attempt polls MARCSTATE until the turnaround bound.
+        assert!(chip.borrow().transactions - transactions > 5 * TX_TURNAROUND_POLLS as usize);
+    }
+
+    #[test]
+    fn test_carrier_sense_threshold() {
+        let chip = RefCell::new(Chip::new());
+        let mut radio = sim::cc1101(&chip);
+        let relative = CarrierSenseRelThr::DISABLED;
+        assert!(matches!(
+            radio.set_carrier_sense_threshold(Some(8), relative),
+            Err(Error::InvalidArgument)
+        ));
+        assert!(matches!(
+            radio.set_carrier_sense_threshold(Some(-8), relative),
+            Err(Error::InvalidArgument)
+        ));
+
+        radio.set_carrier_sense_threshold(Some(-7), relative).unwrap();
+        assert_eq!(chip.borrow().config[Config::AGCCTRL1 as usize], 0x09);
+        radio.set_carrier_sense_threshold(Some(7), relative).unwrap();
+        assert_eq!(chip.borrow().config[Config::AGCCTRL1 as usize], 0x07);
+        radio.set_carrier_sense_threshold(None, CarrierSenseRelThr::INCREASE_10DB).unwrap();
+        assert_eq!(chip.borrow().config[Config::AGCCTRL1 as usize], 0x28);
+    }
+
+    #[test]
+    fn test_receive_rx_off_mode() {
+        let chip = rx_chip(LengthConfig::VARIABLE, AddressCheck::DISABLED);
+        chip.borrow_mut().set_state(MachineState::RX);
+        let mut radio = sim::cc1101(&chip);
+        radio.set_rx_off_mode(RxOffMode::FSTXON).unwrap();
+
+        let mut buf = [0; 8];
+        assert_eq!(radio.receive(&mut 0, &mut buf).unwrap(), PAYLOAD.len() as u8);
+        assert_eq!(radio.radio.read::<MARCSTATE>().unwrap().marc_state(), Ok(MachineState::FSTXON));
+        // Not flushed outside IDLE, but the whole packet is read, up to the next one.
+        assert_eq!(chip.borrow().rx_bytes(), 1 + PAYLOAD.len() + 2);
+    }
+
+    #[test]
+    fn test_state_machine_config() {
+        let chip = RefCell::new(Chip::new());
+        let mut radio = sim::cc1101(&chip);
+        radio.set_rx_off_mode(RxOffMode::STAY_IN_RX).unwrap();
+        radio.set_tx_off_mode(TxOffMode::FSTXON).unwrap();
+        assert_eq!(chip.borrow().config[Config::MCSM1 as usize], 0x0D);
+        assert_eq!(
+            (radio.rxoff_mode, radio.txoff_mode),
+            (RxOffMode::STAY_IN_RX, TxOffMode::FSTXON)
+        );
+
+        radio.set_auto_calibration(AutoCalibration::TO_IDLE).unwrap();
+        radio.set_po_timeout(PoTimeout::EXPIRE_COUNT_64).unwrap();
+        assert_eq!(chip.borrow().config[Config::MCSM0 as usize], 0x28);
+
+        let rx = RxTermination {
+            on_rssi: true,
+            qualifier: RxTimeQual::SYNC_WORD_OR_PQI,
+            timeout: RxTime::DIV_8,
+        };
+        radio.set_rx_termination(&rx).unwrap();
+        assert_eq!(chip.borrow().config[Config::MCSM2 as usize], 0x1B);
+    }
+
+    #[test]
+    fn test_await_off_modes() {
+        let chip = RefCell::new(Chip::new());
+        let mut radio = sim::cc1101(&chip);
+        let tx_states = [
+            (TxOffMode::IDLE, MachineState::IDLE),
+            (TxOffMode::FSTXON, MachineState::FSTXON),
+            (TxOffMode::STAY_IN_TX, MachineState::TX),
+            (TxOffMode::RX, MachineState::RX),
+        ];
+        for &(mode, state) in tx_states.iter() {
+            radio.set_tx_off_mode(mode).unwrap();
+            chip.borrow_mut().set_state(MachineState::TX);
+            radio.await_tx_off().unwrap();
+            radio.await_machine_state(state).unwrap();
+        }
+
+        let rx_states = [
+            (RxOffMode::IDLE, MachineState::IDLE),
+            (RxOffMode::FSTXON, MachineState::FSTXON),
+            (RxOffMode::STAY_IN_RX, MachineState::RX),
+        ];
+        for &(mode, state) in rx_states.iter() {
+            radio.set_rx_off_mode(mode).unwrap();
+            chip.borrow_mut().set_state(MachineState::RX);
+            chip.borrow_mut().receive(&[0x00]);
+            radio.await_rx_off().unwrap();
+            radio.await_machine_state(state).unwrap();
+            radio.radio.write_strobe(Command::SFRX).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_transmit_then_receive() {
+        let chip = RefCell::new(Chip::new());
+        chip.borrow_mut().config[Config::PKTCTRL0 as usize] = PKTCTRL0::default().bits();
+        chip.borrow_mut().config[Config::PKTCTRL1 as usize] = PKTCTRL1::default().bits();
+        chip.borrow_mut().status[Status::LQI as usize - 0x30] = 0x80;
+        let mut radio = sim::cc1101(&chip);
+        radio.set_tx_off_mode(TxOffMode::FSTXON).unwrap();
+        let mcsm1 = chip.borrow().config[Config::MCSM1 as usize];
+
+        let mut buf = [0; 8];
+        chip.borrow_mut().reply(&[0x02, 0xAB, 0xCD]);
+        let length = radio.transmit_then_receive(&[0x01], 1_000, &mut Delay, &mut 0, &mut buf);
+        assert_eq!(length.unwrap(), 2);
+        assert_eq!(buf[..2], [0xAB, 0xCD]);
+        assert_eq!(chip.borrow().config[Config::MCSM1 as usize], mcsm1);
+
+        let timeout = radio.transmit_then_receive(&[0x01], 1_000, &mut Delay, &mut 0, &mut buf);
+        assert!(matches!(timeout, Err(Error::Timeout)));
+        assert_eq!(chip.borrow().config[Config::MCSM1 as usize], mcsm1);
+
+        // Fail the SIDLE strobe, after TXOFF_MODE and RXOFF_MODE are changed.
+        let transactions = chip.borrow().transactions;
+        chip.borrow_mut().fail_at = Some(transactions + 5);
+        let result = radio.transmit_then_receive(&[0x01], 1_000, &mut Delay, &mut 0, &mut buf);
+        assert!(matches!(result, Err(Error::Spi(Fault))));
+        assert_eq!(chip.borrow().config[Config::MCSM1 as usize], mcsm1);
+        assert_eq!(radio.txoff_mode, TxOffMode::FSTXON);
+    }
+
+    #[test]
+    fn test_set_data_mode() {
+        use GdoRole::*;
+
+        let chip = RefCell::new(Chip::new());
+        let mut radio = sim::cc1101(&chip);
+        let modes = [
+            (DataMode::Packet, 0x00, 0x06, 0x01, SyncWord, RxFifo),
+            (DataMode::SynchronousSerial, 0x10, 0x0C, 0x0B, SerialData, SerialClock),
+            (DataMode::AsynchronousSerial, 0x30, 0x0D, 0x0D, SerialData, SerialData),
+            (DataMode::RandomTx, 0x20, 0x06, 0x01, SyncWord, RxFifo),
+        ];
+        for (mode, pktctrl0, iocfg0, iocfg2, gdo0, gdo2) in modes {
+            let pins = radio.set_data_mode(mode).unwrap();
+            assert_eq!((pins.gdo0, pins.gdo2), (gdo0, gdo2));
+            let chip = chip.borrow();
+            assert_eq!(chip.config[Config::PKTCTRL0 as usize], pktctrl0);
+            assert_eq!(chip.config[Config::IOCFG0 as usize], iocfg0);
+            assert_eq!(chip.config[Config::IOCFG2 as usize], iocfg2);
+        }
+    }
+
+    #[test]
+    fn test_transmit_ook_restores_packet_mode() {
+        let chip = RefCell::new(Chip::new());
+        let packet_mode =
+            [(Config::PKTCTRL0, 0x45), (Config::IOCFG0, 0x06), (Config::IOCFG2, 0x01)];
+        for &(reg, value) in packet_mode.iter() {
+            chip.borrow_mut().config[reg as usize] = value;
+        }
+        let mut radio = sim::cc1101(&chip);
+        let waveform = ook::Waveform::Pulses(&[]);
+        let transactions = chip.borrow().transactions;
+        radio.transmit_ook(&mut Pin, &waveform, 1, &mut Delay).unwrap();
+        for &(reg, value) in packet_mode.iter() {
+            assert_eq!(chip.borrow().config[reg as usize], value);
+        }
+
+        // Fail the final SIDLE strobe, followed by two MARCSTATE reads and the three writes.
+        let sidle = chip.borrow().transactions - transactions - 5;
+        let transactions = chip.borrow().transactions;
+        chip.borrow_mut().fail_at = Some(transactions + sidle);
+        let result = radio.transmit_ook(&mut Pin, &waveform, 1, &mut Delay);
+        assert!(matches!(result, Err(Error::Spi(Fault))));
+        for &(reg, value) in packet_mode.iter() {
+            assert_eq!(chip.borrow().config[reg as usize], value);
+        }
+
+        // Fail the SIDLE strobe on the way to TX, after the data mode is changed.
+        let transactions = chip.borrow().transactions;
+        chip.borrow_mut().fail_at = Some(transactions + 11);
+        let result = radio.transmit_ook(&mut Pin, &waveform, 1, &mut Delay);
+        assert!(matches!(result, Err(Error::Spi(Fault))));
+        for &(reg, value) in packet_mode.iter() {
+            assert_eq!(chip.borrow().config[reg as usize], value);
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_transmit_sub_file_without_raw_data() {
+        let file = flipper::SubFile::parse(
+            "Filetype: Flipper SubGhz Key File
+Frequency: 433920000
+Preset: FuriHalSubGhzPresetOok650Async
+Protocol: Princeton
+Key: 00 00 00 00 00 95 D5 D4
+",
+        )
+        .unwrap();
+        let chip = RefCell::new(Chip::new());
+        let mut radio = sim::cc1101(&chip);
+        let result = radio.transmit_sub_file(&file, &mut Pin, 1, &mut Delay);
+        assert!(matches!(result, Err(Error::InvalidArgument)));
+        assert_eq!(chip.borrow().transactions, 0);
+    }
+
+    #[test]
+    fn test_set_channel_spacing() {
+        let chip = RefCell::new(Chip::new());
+        chip.borrow_mut().config[Config::MDMCFG1 as usize] = 0x20;
+        let mut radio = sim::cc1101(&chip);
+        radio.set_channel_spacing(200_000).unwrap();
+        assert_eq!(chip.borrow().config[Config::MDMCFG1 as usize..][..2], [0x22, 0xF8]);
+
+        assert!(matches!(radio.set_channel_spacing(25_000), Err(Error::InvalidArgument)));
+        assert!(matches!(radio.set_channel_spacing(405_457), Err(Error::InvalidArgument)));
+        radio.set_channel_spacing(25_390).unwrap();
+        assert_eq!(chip.borrow().config[Config::MDMCFG1 as usize..][..2], [0x20, 0x00]);
+        radio.set_channel_spacing(405_456).unwrap();
+        assert_eq!(chip.borrow().config[Config::MDMCFG1 as usize..][..2], [0x23, 0xFF]);
+    }
+
+    #[test]
+    fn test_set_packet_handling() {
+        let chip = RefCell::new(Chip::new());
+        // Reset values, with fixed packet length.
+        let reset = [
+            (Config::PKTCTRL1, 0x04),
+            (Config::PKTCTRL0, 0x44),
+            (Config::MDMCFG2, 0x13),
+            (Config::MDMCFG1, 0x22),
+        ];
+        for &(reg, value) in reset.iter() {
+            chip.borrow_mut().config[reg as usize] = value;
+        }
+        let mut radio = sim::cc1101(&chip);
+        radio.set_packet_handling(&PacketHandling::default()).unwrap();
+        for &(reg, value) in reset.iter() {
+            assert_eq!(chip.borrow().config[reg as usize], value, "{:#04X}", reg.addr());
+        }
+
+        let packet = PacketHandling {
+            preamble: NumPreamble::N_8,
+            preamble_quality_threshold: 3,
+            crc: true,
+            crc_autoflush: true,
+            whitening: false,
+            fec: true,
+            manchester: true,
+        };
+        radio.set_packet_handling(&packet).unwrap();
+        let expected = [
+            (Config::PKTCTRL1, 0x6C),
+            (Config::PKTCTRL0, 0x04),
+            (Config::MDMCFG2, 0x1B),
+            (Config::MDMCFG1, 0xC2),
+        ];
+        for &(reg, value) in expected.iter() {
+            assert_eq!(chip.borrow().config[reg as usize], value, "{:#04X}", reg.addr());
+        }
+
+        // The defaults keep whitening on, as after reset.
+        radio.set_defaults().unwrap();
+        assert_eq!(chip.borrow().config[Config::PKTCTRL0 as usize] & 0x40, 0x40);
+    }
+
+    #[test]
+    fn test_apply_settings_rssi_offset() {
+        let chip = RefCell::new(Chip::new());
+        let mut radio = sim::cc1101(&chip);
+        // 868 MHz and 250 kBaud.
+        let settings = RegisterSettings::new()
+            .with(Config::FREQ2, 0x21)
+            .with(Config::FREQ1, 0x62)
+            .with(Config::FREQ0, 0x76)
+            .with(Config::MDMCFG4, 0x2D)
+            .with(Config::MDMCFG3, 0x3B);
+        radio.apply_settings(&settings).unwrap();
+        assert_eq!(radio.rssi_dbm(0), -78);
+    }
+
+    #[test]
+    fn test_update_settings() {
+        let chip = RefCell::new(Chip::new());
+        let current = [(Config::FREQ2, 0x10), (Config::FREQ1, 0xA7), (Config::MDMCFG3, 0x83)];
+        for &(reg, value) in current.iter() {
+            chip.borrow_mut().config[reg as usize] = value;
+        }
+        let mut radio = sim::cc1101(&chip);
+
+        // FREQ1 to MDMCFG2 in one burst with MDMCFG3 rewritten, IOCFG0 and the PA table.
+        let target = RegisterSettings::new()
+            .with(Config::IOCFG0, 0x0D)
+            .with(Config::FREQ2, 0x10)
+            .with(Config::FREQ1, 0xB0)
+            .with(Config::FREQ0, 0x71)
+            .with(Config::MDMCFG4, 0xF5)
+            .with(Config::MDMCFG3, 0x83)
+            .with(Config::MDMCFG2, 0x30)
+            .with_pa_table([0x00, 0xC0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(radio.update_settings(&target).unwrap(), 1 + 5 + 8);
+        for (reg, value) in target.iter() {
+            assert_eq!(chip.borrow().config[reg as usize], value, "{:#04X}", reg.addr());
+        }
+        assert_eq!(chip.borrow().pa_table, [0x00, 0xC0, 0, 0, 0, 0, 0, 0]);
+
+        assert_eq!(radio.update_settings(&target).unwrap(), 0);
+
+        // Without a value in the snapshot, a register is written.
+        let snapshot = RegisterSettings::new().with(Config::FREQ2, 0x10);
+        assert_eq!(radio.update_settings_from(&snapshot, &target).unwrap(), 1 + 5 + 8);
+    }
+}
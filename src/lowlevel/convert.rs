@@ -14,10 +14,8 @@ pub const fn from_frequency(hz: u64) -> (u8, u8, u8) {
 pub const fn to_frequency((freq0, freq1, freq2): (u8, u8, u8)) -> u64 {
     // I'm not sure why we need +1, but this was necessary to get
     // stable/reversible output eg. to_frequency(from_frequency(x)) == x
-    let freq0 = freq0 + 1;
-    let freq = ((freq2 as u64) << 16) | ((freq1 as u64) << 8) as u64 | freq0 as u64;
-    let hz = freq * FXOSC / 1u64.rotate_left(16);
-    hz
+    let freq = (((freq2 as u64) << 16) | ((freq1 as u64) << 8) | freq0 as u64) + 1;
+    freq * FXOSC / 1u64.rotate_left(16)
 }
 
 pub const fn from_deviation(v: u64) -> (u8, u8) {
@@ -26,6 +24,11 @@ pub const fn from_deviation(v: u64) -> (u8, u8) {
     ((mantissa & 0x7) as u8, (exponent & 0x7) as u8)
 }
 
+/// to_deviation returns the frequency deviation in Hz.
+pub const fn to_deviation(mantissa: u8, exponent: u8) -> u64 {
+    (FXOSC * (8 + mantissa as u64) * (1 << exponent)) >> 17
+}
+
 // TODO: Not defined for all values, need to figure out.
 pub const fn from_drate(v: u64) -> (u8, u8) {
     let exponent = 64 - (v.rotate_left(19) / FXOSC).leading_zeros();
@@ -43,6 +46,35 @@ pub fn from_chanbw(v: u64) -> (u8, u8) {
     (mantissa as u8 & 0x3, exponent as u8 & 0x3)
 }
 
+/// to_drate returns the symbol rate in baud.
+pub const fn to_drate(mantissa: u8, exponent: u8) -> u64 {
+    (((256 + mantissa as u64) << exponent) * FXOSC) >> 28
+}
+
+/// to_chanbw returns the channel filter bandwidth in Hz.
+pub const fn to_chanbw(mantissa: u8, exponent: u8) -> u64 {
+    FXOSC / (8 * (4 + mantissa as u64) * (1 << exponent))
+}
+
+/// from_chanspc returns the mantissa and exponent of the channel spacing closest to `v` Hz,
+/// with the smallest exponent that can represent it.
+pub fn from_chanspc(v: u64) -> (u8, u8) {
+    let exponent = (0..3).find(|&e| v <= to_chanspc(255, e)).unwrap_or(3);
+    let divisor = FXOSC << exponent;
+    let mantissa = ((v.min(1 << 40) << 18) + divisor / 2) / divisor;
+    ((mantissa.clamp(256, 511) - 256) as u8, exponent)
+}
+
+/// to_chanspc returns the channel spacing in Hz.
+pub const fn to_chanspc(mantissa: u8, exponent: u8) -> u64 {
+    (FXOSC * (256 + mantissa as u64) * (1 << exponent)) >> 18
+}
+
+/// to_frequency_offset returns the offset in Hz of a two's complement FREQEST or FSCTRL0 value.
+pub const fn to_frequency_offset(raw: u8) -> i32 {
+    ((raw as i8) as i64 * FXOSC as i64 / (1 << 14)) as i32
+}
+
 #[cfg(test)]
 mod tests {
     use crate::lowlevel::convert::*;
@@ -62,6 +94,13 @@ mod tests {
         assert_eq!(to_frequency((0xC4, 0x4E, 0x23)), 918_000_030);
     }
 
+    #[test]
+    fn test_frequency_freq0_overflow() {
+        // The +1 is added after combining the bytes, FREQ0 = 0xFF carries into FREQ1.
+        assert_eq!(to_frequency((0xFF, 0xA7, 0x10)), 433_062_500);
+        assert_eq!(to_frequency((0xFF, 0xFF, 0xFF)), 26_000_000 * 256);
+    }
+
     #[test]
     fn test_frequency_stability() {
         let freq = [
@@ -86,7 +125,7 @@ mod tests {
     fn test_deviation() {
         // f_dev = f_osc / 2^17 * (8 + DEVIATION_M) * 2^DEVIATION_E
         fn calc_rev_dev(dev_m: u8, dev_e: u8) -> u64 {
-            (((FXOSC as f32 / (2u64.pow(17) as f32)) as f32)
+            ((FXOSC as f32 / (2u64.pow(17) as f32))
                 * (8f32 + dev_m as f32)
                 * (2u64.pow(dev_e as u32) as f32)) as u64
         }
@@ -94,8 +133,12 @@ mod tests {
         for e in 0..7 {
             for m in 1..7 {
                 assert_eq!(from_deviation(calc_rev_dev(m, e)), (m, e));
+                assert_eq!(from_deviation(to_deviation(m, e)), (m, e));
             }
         }
+
+        // Reset value of DEVIATN.
+        assert_eq!(to_deviation(7, 4), 47607);
     }
 
     #[test]
@@ -115,6 +158,10 @@ mod tests {
         assert_eq!((131, 6), from_drate(2398));
         assert_eq!((131, 5), from_drate(1199));
 
+        assert_eq!(to_drate(117, 5), 1156);
+        assert_eq!(to_drate(34, 12), 115051);
+        assert_eq!(to_drate(59, 14), 499877);
+
         /* TODO: make this work
         fn calc_drate_rev(mantissa: u8, exponent: u8) -> u64 {
             let q = (256.0 + mantissa as f64) * 2f64.powf(exponent as f64);
@@ -149,5 +196,31 @@ mod tests {
         assert_eq!(from_chanbw(81250), (0b01, 0b11));
         assert_eq!(from_chanbw(67708), (0b10, 0b11));
         assert_eq!(from_chanbw(58035), (0b11, 0b11));
+
+        assert_eq!(to_chanbw(0b00, 0b00), 812500);
+        assert_eq!(to_chanbw(0b11, 0b11), 58035);
+    }
+
+    #[test]
+    fn test_frequency_offset() {
+        assert_eq!(to_frequency_offset(0x00), 0);
+        assert_eq!(to_frequency_offset(0x01), 1586);
+        assert_eq!(to_frequency_offset(0x7F), 201538);
+        assert_eq!(to_frequency_offset(0xFF), -1586);
+        assert_eq!(to_frequency_offset(0x80), -203125);
+    }
+
+    #[test]
+    fn test_chanspc() {
+        // Reset values of MDMCFG0 and MDMCFG1.
+        assert_eq!(to_chanspc(0xF8, 0x02), 199951);
+        assert_eq!(to_chanspc(0x00, 0x00), 25390);
+
+        assert_eq!(from_chanspc(199951), (0xF8, 0x02));
+        assert_eq!(from_chanspc(200000), (0xF8, 0x02));
+        assert_eq!(from_chanspc(25390), (0x00, 0x00));
+        assert_eq!(from_chanspc(50682), (0xFF, 0x00));
+        assert_eq!(from_chanspc(0), (0x00, 0x00));
+        assert_eq!(from_chanspc(u64::MAX), (0xFF, 0x03));
     }
 }
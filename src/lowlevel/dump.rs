@@ -0,0 +1,172 @@
+use core::fmt;
+
+use crate::lowlevel::convert::*;
+use crate::lowlevel::registers::*;
+use crate::lowlevel::Field;
+
+/// Snapshot of all configuration and status registers.
+///
+/// The [`Display`](fmt::Display) output lists the derived radio settings followed by every
+/// register with its bitfields decoded by name.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RegisterDump {
+    config: [u8; Config::ALL.len()],
+    status: [u8; Status::ALL.len()],
+}
+
+impl RegisterDump {
+    /// A dump of register values, in address order.
+    pub fn new(config: [u8; Config::ALL.len()], status: [u8; Status::ALL.len()]) -> Self {
+        RegisterDump {
+            config,
+            status,
+        }
+    }
+
+    /// The value of a configuration register.
+    pub fn config(&self, reg: Config) -> u8 {
+        self.config[reg.addr() as usize]
+    }
+
+    /// The value of a status register.
+    pub fn status(&self, reg: Status) -> u8 {
+        self.status[(reg.addr() - Status::PARTNUM.addr()) as usize]
+    }
+
+    /// Base frequency in Hz.
+    pub fn frequency(&self) -> u64 {
+        FREQ(u32::from_be_bytes([
+            0,
+            self.config(Config::FREQ2),
+            self.config(Config::FREQ1),
+            self.config(Config::FREQ0),
+        ]))
+        .hz()
+    }
+
+    /// Symbol rate in baud.
+    pub fn data_rate(&self) -> u64 {
+        to_drate(
+            MDMCFG3(self.config(Config::MDMCFG3)).drate_m(),
+            MDMCFG4(self.config(Config::MDMCFG4)).drate_e(),
+        )
+    }
+
+    /// Channel filter bandwidth in Hz.
+    pub fn channel_bandwidth(&self) -> u64 {
+        let mdmcfg4 = MDMCFG4(self.config(Config::MDMCFG4));
+        to_chanbw(mdmcfg4.chanbw_m(), mdmcfg4.chanbw_e())
+    }
+
+    /// Frequency deviation in Hz.
+    pub fn deviation(&self) -> u64 {
+        let deviatn = DEVIATN(self.config(Config::DEVIATN));
+        to_deviation(deviatn.deviation_m(), deviatn.deviation_e())
+    }
+
+    /// Channel spacing in Hz.
+    pub fn channel_spacing(&self) -> u64 {
+        to_chanspc(
+            MDMCFG0(self.config(Config::MDMCFG0)).chanspc_m(),
+            MDMCFG1(self.config(Config::MDMCFG1)).chanspc_e(),
+        )
+    }
+}
+
+fn write_register(
+    f: &mut fmt::Formatter,
+    addr: u8,
+    name: &str,
+    value: u8,
+    fields: &[Field],
+) -> fmt::Result {
+    write!(f, "0x{:02X} {:<14} 0x{:02X}", addr, name, value)?;
+    for field in fields {
+        write!(f, " {}={}", field.name, field.get(value))?;
+    }
+    writeln!(f)
+}
+
+impl fmt::Display for RegisterDump {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "frequency         {} Hz", self.frequency())?;
+        writeln!(f, "data rate         {} Bd", self.data_rate())?;
+        writeln!(f, "channel bandwidth {} Hz", self.channel_bandwidth())?;
+        writeln!(f, "deviation         {} Hz", self.deviation())?;
+        writeln!(f, "channel spacing   {} Hz", self.channel_spacing())?;
+
+        for reg in Config::ALL.iter() {
+            write_register(f, reg.addr(), reg.name(), self.config(*reg), reg.fields())?;
+        }
+        for reg in Status::ALL.iter() {
+            write_register(f, reg.addr(), reg.name(), self.status(*reg), reg.fields())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lowlevel::dump::*;
+    use core::fmt::Write;
+
+    // Fixed size buffer, as there is no String without alloc.
+    struct Buffer {
+        bytes: [u8; 4096],
+        len: usize,
+    }
+
+    impl Write for Buffer {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            let end = self.len + s.len();
+            self.bytes.get_mut(self.len..end).ok_or(fmt::Error)?.copy_from_slice(s.as_bytes());
+            self.len = end;
+            Ok(())
+        }
+    }
+
+    fn reset_dump() -> RegisterDump {
+        let mut config = [0; Config::ALL.len()];
+        config[Config::FREQ2 as usize] = 0x10;
+        config[Config::FREQ1 as usize] = 0xA7;
+        config[Config::FREQ0 as usize] = 0x62;
+        config[Config::MDMCFG4 as usize] = 0x8C;
+        config[Config::MDMCFG3 as usize] = 0x22;
+        config[Config::MDMCFG2 as usize] = 0x02;
+        config[Config::MDMCFG1 as usize] = 0x22;
+        config[Config::MDMCFG0 as usize] = 0xF8;
+        config[Config::DEVIATN as usize] = 0x47;
+
+        let mut status = [0; Status::ALL.len()];
+        status[Status::VERSION as usize - 0x30] = 0x14;
+        status[Status::MARCSTATE as usize - 0x30] = 0x01;
+        RegisterDump::new(config, status)
+    }
+
+    #[test]
+    fn test_derived_values() {
+        let dump = reset_dump();
+        assert_eq!(dump.frequency(), 432_999_816);
+        assert_eq!(dump.data_rate(), 115_051);
+        assert_eq!(dump.channel_bandwidth(), 203_125);
+        assert_eq!(dump.deviation(), 47_607);
+        assert_eq!(dump.channel_spacing(), 199_951);
+        assert_eq!(dump.status(Status::VERSION), 0x14);
+    }
+
+    #[test]
+    fn test_display() {
+        let mut buffer = Buffer {
+            bytes: [0; 4096],
+            len: 0,
+        };
+        write!(buffer, "{}", reset_dump()).unwrap();
+        let text = core::str::from_utf8(&buffer.bytes[..buffer.len]).unwrap();
+
+        let mut lines = text.lines();
+        assert_eq!(lines.next(), Some("frequency         432999816 Hz"));
+        assert!(text.contains("\n0x10 MDMCFG4        0x8C chanbw_e=2 chanbw_m=0 drate_e=12\n"));
+        assert!(text.contains("\n0x35 MARCSTATE      0x01 marc_state=1\n"));
+        assert_eq!(lines.count(), 4 + Config::ALL.len() + Status::ALL.len());
+    }
+}
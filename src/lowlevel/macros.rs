@@ -1,14 +1,61 @@
 macro_rules! register {
+    // Bitfields with an enum type read as the enum, or the raw bits if they match no variant.
+    (@type $uxx:ty,) => { $uxx };
+    (@type $uxx:ty, $ty:ty) => { Result<$ty, $uxx> };
+
+    (@arg $uxx:ty,) => { $uxx };
+    (@arg $uxx:ty, $ty:ty) => { $ty };
+
+    (@read $bits:expr, $uxx:ty,) => { $bits };
+    (@read $bits:expr, $uxx:ty, $ty:ty) => {
+        <$ty as ::core::convert::TryFrom<$uxx>>::try_from($bits)
+    };
+
+    (@write $value:expr,) => { $value };
+    (@write $value:expr, $ty:ty) => { $value.value() };
+
+    (@debug $f:ident, $name:expr, $bits:expr, $uxx:ty,) => { $f.field($name, &$bits) };
+    (@debug $f:ident, $name:expr, $bits:expr, $uxx:ty, $ty:ty) => {
+        match <$ty as ::core::convert::TryFrom<$uxx>>::try_from($bits) {
+            Ok(value) => $f.field($name, &value),
+            Err(bits) => $f.field($name, &bits),
+        }
+    };
+
     ($REGISTER:ident, $reset_value:expr, $uxx:ty, {
-        $(#[$($attr:tt)*] $bitfield:ident @ $range:expr,)+
+        $(#[$($attr:tt)*] $bitfield:ident @ $offset:literal $(..$end:literal)? $(: $ty:ty)?,)+
     }) => {
         #[allow(non_camel_case_types)]
         #[derive(Clone, Copy)]
-        pub struct $REGISTER<MODE> {
+        pub struct $REGISTER<MODE = crate::lowlevel::traits::R> {
             bits: $uxx,
             _mode: ::core::marker::PhantomData<MODE>,
         }
 
+        impl<MODE> $REGISTER<MODE> {
+            /// Register name as in the datasheet.
+            pub const NAME: &'static str = stringify!($REGISTER);
+
+            /// Register value after reset.
+            pub const RESET: $uxx = $reset_value;
+
+            /// Bitfields of the register, most significant first.
+            pub const FIELDS: &'static [crate::lowlevel::Field] = &[
+                $(crate::lowlevel::Field::new(stringify!($bitfield), &[$offset $(, $end)?], $reset_value),)+
+            ];
+        }
+
+        impl<MODE> ::core::fmt::Debug for $REGISTER<MODE> {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                let mut s = f.debug_struct(stringify!($REGISTER));
+                $(
+                    let field = crate::lowlevel::Field::new(stringify!($bitfield), &[$offset $(, $end)?], $reset_value);
+                    register!(@debug s, field.name, field.get(self.bits), $uxx, $($ty)?);
+                )+
+                s.finish()
+            }
+        }
+
         impl $REGISTER<crate::lowlevel::traits::Mask> {
             pub fn mask() -> $REGISTER<crate::lowlevel::traits::Mask> {
                 $REGISTER { bits: 0, _mode: ::core::marker::PhantomData }
@@ -16,11 +63,8 @@ macro_rules! register {
 
             $(
                 pub fn $bitfield(&self) -> $uxx {
-                    use crate::lowlevel::traits::OffsetSize;
-
-                    let size = $range.size() + 1;
-                    let offset = $range.offset();
-                    (((1 << size) - 1) as u8) << offset
+                    let field = crate::lowlevel::Field::new(stringify!($bitfield), &[$offset $(, $end)?], $reset_value);
+                    field.mask() << field.offset
                 }
             )+
         }
@@ -43,14 +87,9 @@ macro_rules! register {
 
             $(
                 #[$($attr)*]
-                pub fn $bitfield(&self) -> $uxx {
-                    use crate::lowlevel::traits::OffsetSize;
-
-                    let offset = $range.offset();
-                    let size = $range.size() + 1;
-                    let mask = ((1 << size) - 1) as u8;
-
-                    (self.bits >> offset) & mask
+                pub fn $bitfield(&self) -> register!(@type $uxx, $($ty)?) {
+                    let field = crate::lowlevel::Field::new(stringify!($bitfield), &[$offset $(, $end)?], $reset_value);
+                    register!(@read field.get(self.bits), $uxx, $($ty)?)
                 }
             )+
         }
@@ -62,18 +101,16 @@ macro_rules! register {
 
             $(
                 #[$($attr)*]
-                pub fn $bitfield(&mut self, mut bits: $uxx) -> &mut Self {
-                    use crate::lowlevel::traits::OffsetSize;
-
-                    let offset = $range.offset();
-                    let size = $range.size() + 1;
-                    let mask = ((1 << size) - 1) as u8;
+                pub fn $bitfield(&mut self, value: register!(@arg $uxx, $($ty)?)) -> &mut Self {
+                    let field = crate::lowlevel::Field::new(stringify!($bitfield), &[$offset $(, $end)?], $reset_value);
+                    let mask = field.mask();
+                    let mut bits = register!(@write value, $($ty)?);
 
                     debug_assert!(bits <= mask);
                     bits &= mask;
 
-                    self.bits &= !(mask << offset);
-                    self.bits |= bits << offset;
+                    self.bits &= !(mask << field.offset);
+                    self.bits |= bits << field.offset;
 
                     self
                 }
@@ -81,3 +118,29 @@ macro_rules! register {
         }
     }
 }
+
+// Implements `Register` for the register types named as the `$Address` variants.
+macro_rules! register_addresses {
+    ($Address:ident { $($REGISTER:ident,)+ }) => {
+        $(
+            impl crate::lowlevel::traits::Register for $REGISTER<crate::lowlevel::traits::R> {
+                type Address = $Address;
+                type Writer = $REGISTER<crate::lowlevel::traits::W>;
+
+                const ADDRESS: $Address = $Address::$REGISTER;
+
+                fn from_bits(bits: u8) -> Self {
+                    $REGISTER(bits)
+                }
+
+                fn writer(self) -> Self::Writer {
+                    self.modify()
+                }
+
+                fn into_bits(writer: Self::Writer) -> u8 {
+                    writer.bits()
+                }
+            }
+        )+
+    }
+}
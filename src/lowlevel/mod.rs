@@ -5,20 +5,34 @@ use hal::digital::v2::OutputPin;
 #[macro_use]
 mod macros;
 mod access;
+mod shadow;
+#[cfg(test)]
+pub(crate) mod sim;
 mod traits;
 
 pub mod convert;
+pub mod dump;
 pub mod pa_table;
 pub mod registers;
 pub mod types;
 
+pub use self::traits::{Composite, Field, Register};
+
+use self::dump::RegisterDump;
 use self::registers::*;
+use self::shadow::Shadow;
 
 pub const FXOSC: u64 = 26_000_000;
 
+// Registers in the longest composite value, FREQ. `Composite` is sealed, so no other values
+// come along.
+const COMPOSITE_MAX_LEN: usize = 3;
+
 pub struct Cc1101<SPI, CS> {
     pub(crate) spi: SPI,
     pub(crate) cs: CS,
+    // Configuration registers and PA table as last written or read, if enabled.
+    cache: Option<Shadow>,
     //    gdo0: GDO0,
     //    gdo2: GDO2,
 }
@@ -27,6 +41,8 @@ pub struct Cc1101<SPI, CS> {
 pub enum Error<SpiE, GpioE> {
     Spi(SpiE),
     Gpio(GpioE),
+    /// A register range reaches past the last register of its kind.
+    InvalidArgument,
 }
 
 impl<SPI, CS, SpiE, GpioE> Cc1101<SPI, CS>
@@ -35,21 +51,231 @@ where
     CS: OutputPin<Error = GpioE>,
 {
     pub fn new(spi: SPI, cs: CS) -> Result<Self, Error<SpiE, GpioE>> {
-        let cc1101 = Cc1101 { spi: spi, cs: cs };
+        let cc1101 = Cc1101 {
+            spi,
+            cs,
+            cache: None,
+        };
         Ok(cc1101)
     }
 
+    /// Enable or disable the shadow cache of the configuration registers and the PA table.
+    ///
+    /// Register values are cached as they are written or read, and then read from the cache
+    /// without SPI access. The cache is cleared by the `SRES`, `SPWD` and `SWOR` strobes, as the
+    /// chip loses part of its configuration in SLEEP. [`Cc1101::sync`] fills the cache from the
+    /// chip.
+    pub fn set_cache(&mut self, enabled: bool) {
+        if enabled != self.cache.is_some() {
+            self.cache = if enabled {
+                Some(Shadow::new())
+            } else {
+                None
+            };
+        }
+    }
+
+    /// Fill the cache with all configuration registers and the PA table read from the chip.
+    pub fn sync(&mut self) -> Result<(), Error<SpiE, GpioE>> {
+        let (config, pa_table) = self.read_config_uncached()?;
+        if let Some(cache) = &mut self.cache {
+            cache.set_burst(0, &config);
+            cache.set_pa_table(&pa_table);
+        }
+        Ok(())
+    }
+
+    /// Whether the cached values match the chip, always true if the cache is disabled.
+    pub fn verify(&mut self) -> Result<bool, Error<SpiE, GpioE>> {
+        if self.cache.is_none() {
+            return Ok(true);
+        }
+        let (config, pa_table) = self.read_config_uncached()?;
+        Ok(self.cache.as_ref().map_or(true, |cache| cache.matches(&config, &pa_table)))
+    }
+
+    fn read_config_uncached(
+        &mut self,
+    ) -> Result<([u8; Config::ALL.len()], [u8; 8]), Error<SpiE, GpioE>> {
+        let mut config = [0; Config::ALL.len()];
+        self.read_burst_uncached(Config::IOCFG2.addr(), &mut config)?;
+        let mut pa_table = [0; 8];
+        self.read_burst_uncached(Command::PATABLE.addr(), &mut pa_table)?;
+        Ok((config, pa_table))
+    }
+
     pub fn read_register<R>(&mut self, reg: R) -> Result<u8, Error<SpiE, GpioE>>
     where
-        R: Into<Register>,
+        R: Into<registers::Register>,
     {
+        let reg = reg.into();
+        if let (registers::Register::Config(config), Some(cache)) = (reg, &self.cache) {
+            if let Some(value) = cache.get(config.addr()) {
+                return Ok(value);
+            }
+        }
+
+        let value = self.read_register_uncached(reg)?;
+        if let (registers::Register::Config(config), Some(cache)) = (reg, &mut self.cache) {
+            cache.set_burst(config.addr(), &[value]);
+        }
+        Ok(value)
+    }
+
+    fn read_register_uncached(
+        &mut self,
+        reg: registers::Register,
+    ) -> Result<u8, Error<SpiE, GpioE>> {
         self.cs.set_low().map_err(Error::Gpio)?;
-        let mut buffer = [reg.into().raddr(), 0u8];
+        let mut buffer = [reg.raddr(), 0u8];
         self.spi.transfer(&mut buffer).map_err(Error::Spi)?;
         self.cs.set_high().map_err(Error::Gpio)?;
         Ok(buffer[1])
     }
 
+    /// Read the PA table.
+    pub fn read_pa_table(&mut self) -> Result<[u8; 8], Error<SpiE, GpioE>> {
+        if let Some(pa_table) = self.cache.as_ref().and_then(Shadow::pa_table) {
+            return Ok(pa_table);
+        }
+        let mut pa_table = [0; 8];
+        self.read_burst_uncached(Command::PATABLE.addr(), &mut pa_table)?;
+        if let Some(cache) = &mut self.cache {
+            cache.set_pa_table(&pa_table);
+        }
+        Ok(pa_table)
+    }
+
+    /// Read all configuration and status registers from the chip.
+    pub fn dump_registers(&mut self) -> Result<RegisterDump, Error<SpiE, GpioE>> {
+        let (config, _) = self.read_config_uncached()?;
+        let mut status = [0; Status::ALL.len()];
+        for (value, reg) in status.iter_mut().zip(Status::ALL.iter()) {
+            *value = self.read_register_uncached((*reg).into())?;
+        }
+        Ok(RegisterDump::new(config, status))
+    }
+
+    /// Read a register as its bitfield type, e.g. `read::<MDMCFG2>()`.
+    pub fn read<T>(&mut self) -> Result<T, Error<SpiE, GpioE>>
+    where
+        T: Register,
+    {
+        Ok(T::from_bits(self.read_register(T::ADDRESS)?))
+    }
+
+    /// Read a configuration register, change bitfields with `f` and write it back, e.g.
+    /// `modify::<PKTCTRL1>(|r, w| w.append_status(r.append_status() ^ 1))`.
+    pub fn modify<T>(
+        &mut self,
+        f: impl for<'w> FnOnce(&T, &'w mut T::Writer) -> &'w mut T::Writer,
+    ) -> Result<(), Error<SpiE, GpioE>>
+    where
+        T: Register<Address = Config>,
+    {
+        let r = self.read::<T>()?;
+        let mut w = r.writer();
+        f(&r, &mut w);
+        self.write_register(T::ADDRESS, T::into_bits(w))
+    }
+
+    /// Read a value split across registers, such as [`FREQ`], in one burst access.
+    pub fn read_composite<T>(&mut self) -> Result<T, Error<SpiE, GpioE>>
+    where
+        T: Composite,
+    {
+        let mut bytes = [0u8; COMPOSITE_MAX_LEN];
+        let bytes = &mut bytes[..T::MASKS.len()];
+        self.read_burst(T::START.addr(), bytes)?;
+        Ok(T::from_bytes(bytes))
+    }
+
+    /// Write a value split across registers, such as [`FREQ`], in one burst access.
+    ///
+    /// The registers are read first if they have bits that do not belong to the value.
+    pub fn write_composite<T>(&mut self, value: &T) -> Result<(), Error<SpiE, GpioE>>
+    where
+        T: Composite,
+    {
+        let mut current = [0u8; COMPOSITE_MAX_LEN];
+        let current = &mut current[..T::MASKS.len()];
+        if T::MASKS.iter().any(|&mask| mask != 0xFF) {
+            self.read_burst(T::START.addr(), current)?;
+        }
+
+        let mut bytes = [0u8; COMPOSITE_MAX_LEN];
+        let bytes = &mut bytes[..T::MASKS.len()];
+        value.to_bytes(bytes);
+        for ((byte, current), mask) in bytes.iter_mut().zip(current.iter()).zip(T::MASKS) {
+            *byte = (*current & !mask) | (*byte & mask);
+        }
+        self.write_register_burst(T::START, bytes)
+    }
+
+    /// Read consecutive registers from `reg` in one burst. Configuration registers are read
+    /// from the cache if all are known.
+    ///
+    /// Status registers can not be read in bursts, the burst bit only tells them apart from
+    /// command strobes. They are read one at a time, up to RCCTRL0_STATUS, anything past it
+    /// returns [`Error::InvalidArgument`].
+    pub fn read_register_burst<R>(
+        &mut self,
+        reg: R,
+        bytes: &mut [u8],
+    ) -> Result<(), Error<SpiE, GpioE>>
+    where
+        R: Into<registers::Register>,
+    {
+        match reg.into() {
+            registers::Register::Config(config) => self.read_burst(config.addr(), bytes),
+            registers::Register::Status(status) => {
+                let first = (status.addr() - Status::PARTNUM.addr()) as usize;
+                if first + bytes.len() > Status::ALL.len() {
+                    return Err(Error::InvalidArgument);
+                }
+                for (byte, &reg) in bytes.iter_mut().zip(&Status::ALL[first..]) {
+                    *byte = self.read_register_uncached(reg.into())?;
+                }
+                Ok(())
+            }
+            reg => self.read_burst_uncached(reg.waddr(), bytes),
+        }
+    }
+
+    fn read_burst(&mut self, addr: u8, bytes: &mut [u8]) -> Result<(), Error<SpiE, GpioE>> {
+        if let Some(cache) = &self.cache {
+            if cache.get_burst(addr, bytes) {
+                return Ok(());
+            }
+        }
+        self.read_burst_uncached(addr, bytes)?;
+        if let Some(cache) = &mut self.cache {
+            cache.set_burst(addr, bytes);
+        }
+        Ok(())
+    }
+
+    fn read_burst_uncached(
+        &mut self,
+        addr: u8,
+        bytes: &mut [u8],
+    ) -> Result<(), Error<SpiE, GpioE>> {
+        let read_burst = 0xC0;
+        self.cs.set_low().map_err(Error::Gpio)?;
+        self.spi.write(&[addr | read_burst]).map_err(Error::Spi)?;
+        self.spi.transfer(bytes).map_err(Error::Spi)?;
+        self.cs.set_high().map_err(Error::Gpio)?;
+        Ok(())
+    }
+
+    /// Read bytes from the RX FIFO as they are, without parsing a packet.
+    pub fn read_fifo_raw(&mut self, buf: &mut [u8]) -> Result<(), Error<SpiE, GpioE>> {
+        self.read_burst_uncached(Command::FIFO.addr(), buf)
+    }
+
+    /// Read a packet with a length byte and address byte from the RX FIFO. The first two bytes
+    /// are stored in `len` and `addr`, the rest of the burst in `buf`.
+    #[deprecated(note = "use `read_fifo_raw` and parse the length and address bytes")]
     pub fn read_fifo(
         &mut self,
         addr: &mut u8,
@@ -57,16 +283,16 @@ where
         buf: &mut [u8],
     ) -> Result<(), Error<SpiE, GpioE>> {
         let read_burst = 0xC0;
-        let mut buffer = [Command::FIFO.addr() | read_burst, 0, 0];
+        let mut buffer = [0u8; 2];
 
         self.cs.set_low().map_err(Error::Gpio)?;
+        self.spi.write(&[Command::FIFO.addr() | read_burst]).map_err(Error::Spi)?;
         self.spi.transfer(&mut buffer).map_err(Error::Spi)?;
         self.spi.transfer(buf).map_err(Error::Spi)?;
         self.cs.set_high().map_err(Error::Gpio)?;
 
-        *len = buffer[1];
-        *addr = buffer[2];
-
+        *len = buffer[0];
+        *addr = buffer[1];
         Ok(())
     }
 
@@ -74,24 +300,43 @@ where
         self.cs.set_low().map_err(Error::Gpio)?;
         self.spi.write(&[com.addr()]).map_err(Error::Spi)?;
         self.cs.set_high().map_err(Error::Gpio)?;
+
+        // The configuration is reset, or lost in SLEEP, also when entered periodically by WOR.
+        if matches!(com, Command::SRES | Command::SPWD | Command::SWOR) {
+            if let Some(cache) = &mut self.cache {
+                *cache = Shadow::new();
+            }
+        }
         Ok(())
     }
 
     pub fn write_register<R>(&mut self, reg: R, byte: u8) -> Result<(), Error<SpiE, GpioE>>
     where
-        R: Into<Register>,
+        R: Into<registers::Register>,
     {
+        let reg = reg.into();
         self.cs.set_low().map_err(Error::Gpio)?;
-        self.spi
-            .write(&mut [reg.into().waddr(), byte])
-            .map_err(Error::Spi)?;
+        self.spi.write(&[reg.waddr(), byte]).map_err(Error::Spi)?;
         self.cs.set_high().map_err(Error::Gpio)?;
+        self.update_cache(reg, &[byte]);
         Ok(())
     }
 
+    fn update_cache(&mut self, reg: registers::Register, bytes: &[u8]) {
+        match (reg, &mut self.cache) {
+            (registers::Register::Config(config), Some(cache)) => {
+                cache.set_burst(config.addr(), bytes)
+            }
+            (registers::Register::Command(Command::PATABLE), Some(cache)) => {
+                cache.set_pa_table(bytes)
+            }
+            _ => {}
+        }
+    }
+
     pub fn modify_register<R, F>(&mut self, reg: R, f: F) -> Result<(), Error<SpiE, GpioE>>
     where
-        R: Into<Register> + Copy,
+        R: Into<registers::Register> + Copy,
         F: FnOnce(u8) -> u8,
     {
         let r = self.read_register(reg)?;
@@ -105,16 +350,136 @@ where
         bytes: &[u8],
     ) -> Result<(), Error<SpiE, GpioE>>
     where
-        R: Into<Register>,
+        R: Into<registers::Register>,
     {
+        let reg = reg.into();
         let write_burst = 0x40;
         self.cs.set_low().map_err(Error::Gpio)?;
         // FIXME: confirm we can actually split the write like this?
-        self.spi
-            .write(&[reg.into().waddr() | write_burst])
-            .map_err(Error::Spi)?;
+        self.spi.write(&[reg.waddr() | write_burst]).map_err(Error::Spi)?;
         self.spi.write(bytes).map_err(Error::Spi)?;
         self.cs.set_high().map_err(Error::Gpio)?;
+        self.update_cache(reg, bytes);
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::lowlevel::registers::*;
+    use crate::lowlevel::sim::{self, Chip};
+    use crate::lowlevel::Error;
+    use core::cell::RefCell;
+
+    #[test]
+    fn test_cache_reads() {
+        let chip = RefCell::new(Chip::new());
+        chip.borrow_mut().config[Config::FREQ1 as usize] = 0xA7;
+        let mut radio = sim::radio(&chip);
+
+        // Without the cache every read is a transaction.
+        radio.read_register(Config::FREQ1).unwrap();
+        radio.read_register(Config::FREQ1).unwrap();
+        assert_eq!(chip.borrow().transactions, 2);
+
+        radio.set_cache(true);
+        radio.write_register(Config::MDMCFG2, 0x30).unwrap();
+        radio.write_register_burst(Command::PATABLE, &[0x00, 0xC0]).unwrap();
+        assert_eq!(radio.read_register(Config::MDMCFG2).unwrap(), 0x30);
+        assert_eq!(radio.read_register(Config::FREQ1).unwrap(), 0xA7);
+        assert_eq!(radio.read_register(Config::FREQ1).unwrap(), 0xA7);
+        assert_eq!(chip.borrow().transactions, 5);
+
+        radio.sync().unwrap();
+        let transactions = chip.borrow().transactions;
+        assert_eq!(radio.read_composite::<FREQ>().unwrap(), FREQ(0x00A700));
+        assert_eq!(radio.read_pa_table().unwrap(), [0x00, 0xC0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(chip.borrow().transactions, transactions);
+
+        // Calibration results are always read from the chip.
+        radio.read_register(Config::FSCAL1).unwrap();
+        assert_eq!(chip.borrow().transactions, transactions + 1);
+    }
+
+    #[test]
+    fn test_cache_invalidate() {
+        let chip = RefCell::new(Chip::new());
+        let mut radio = sim::radio(&chip);
+        radio.set_cache(true);
+        radio.write_register(Config::PKTLEN, 0x20).unwrap();
+
+        radio.write_strobe(Command::SRES).unwrap();
+        assert_eq!(radio.read_register(Config::PKTLEN).unwrap(), 0x00);
+
+        radio.write_register(Config::PKTLEN, 0x20).unwrap();
+        radio.write_strobe(Command::SPWD).unwrap();
+        chip.borrow_mut().config[Config::PKTLEN as usize] = 0xFF;
+        assert_eq!(radio.read_register(Config::PKTLEN).unwrap(), 0xFF);
+
+        radio.write_register(Config::PKTLEN, 0x20).unwrap();
+        radio.write_strobe(Command::SWOR).unwrap();
+        chip.borrow_mut().config[Config::PKTLEN as usize] = 0xFF;
+        assert_eq!(radio.read_register(Config::PKTLEN).unwrap(), 0xFF);
+    }
+
+    #[test]
+    fn test_cache_verify() {
+        let chip = RefCell::new(Chip::new());
+        let mut radio = sim::radio(&chip);
+        assert!(radio.verify().unwrap());
+        assert_eq!(chip.borrow().transactions, 0);
+
+        radio.set_cache(true);
+        radio.sync().unwrap();
+        assert!(radio.verify().unwrap());
+
+        chip.borrow_mut().config[Config::MCSM1 as usize] = 0x30;
+        assert!(!radio.verify().unwrap());
+        chip.borrow_mut().config[Config::MCSM1 as usize] = 0x00;
+        chip.borrow_mut().pa_table[3] = 0x60;
+        assert!(!radio.verify().unwrap());
+
+        // Calibration results are not compared.
+        chip.borrow_mut().pa_table[3] = 0x00;
+        chip.borrow_mut().config[Config::FSCAL3 as usize] = 0xE9;
+        assert!(radio.verify().unwrap());
+    }
+
+    #[test]
+    fn test_read_register_burst() {
+        let chip = RefCell::new(Chip::new());
+        chip.borrow_mut().config[Config::SYNC1 as usize..][..2].copy_from_slice(&[0xD3, 0x91]);
+        chip.borrow_mut().status[..2].copy_from_slice(&[0x00, 0x14]);
+        let mut radio = sim::radio(&chip);
+
+        let mut bytes = [0; 2];
+        radio.read_register_burst(Config::SYNC1, &mut bytes).unwrap();
+        assert_eq!(bytes, [0xD3, 0x91]);
+        radio.read_register_burst(Status::PARTNUM, &mut bytes).unwrap();
+        assert_eq!(bytes, [0x00, 0x14]);
+        radio.read_register_burst(Command::PATABLE, &mut bytes).unwrap();
+        assert_eq!(bytes, [0x00, 0x00]);
+        // Status registers are read one at a time.
+        assert_eq!(chip.borrow().transactions, 4);
+
+        // Past RCCTRL0_STATUS there are only command strobes.
+        let mut bytes = [0; 3];
+        assert!(matches!(
+            radio.read_register_burst(Status::RCCTRL0_STATUS, &mut bytes),
+            Err(Error::InvalidArgument)
+        ));
+        assert_eq!(chip.borrow().transactions, 4);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_read_fifo() {
+        let chip = RefCell::new(Chip::new());
+        chip.borrow_mut().receive(&[0x03, 0x42, 0xAA, 0xBB, 0xCC]);
+        let mut radio = sim::radio(&chip);
+
+        let (mut addr, mut len, mut buf) = (0, 0, [0; 3]);
+        radio.read_fifo(&mut addr, &mut len, &mut buf).unwrap();
+        assert_eq!((len, addr, buf), (0x03, 0x42, [0xAA, 0xBB, 0xCC]));
+    }
+}
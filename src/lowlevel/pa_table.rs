@@ -39,8 +39,7 @@ impl Index<usize> for PaColumn {
 
 /// pa_table gets the PA table column closest matching the provided frequency.
 pub const fn pa_table(hz: u64) -> PaColumn {
-    let table = PA_TABLE[table_column_index(hz)];
-    table
+    PA_TABLE[table_column_index(hz)]
 }
 
 /// table_column_index returns the column index closest to the provided frequency -
@@ -59,10 +58,10 @@ const fn table_column_index(hz: u64) -> usize {
 pub const fn output_power_row_index(output_dbm: i32) -> usize {
     match output_dbm {
         i32::MIN..=-30 => 0,
-        -31..=-20 => 1,
-        -21..=-15 => 2,
-        -16..=-10 => 3,
-        -11..=-6 => 4, // This and the following was merged.
+        -29..=-20 => 1,
+        -19..=-15 => 2,
+        -14..=-10 => 3,
+        -9..=-6 => 4, // This and the following was merged.
         -5..=0 => 5,   //
         1..=5 => 6,
         6..=7 => 7,
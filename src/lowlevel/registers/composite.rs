@@ -0,0 +1,203 @@
+use super::Config;
+use crate::lowlevel::convert::*;
+use crate::lowlevel::traits::Composite;
+use crate::lowlevel::FXOSC;
+
+/// Base frequency word, FREQ\[23:0\] in FREQ2, FREQ1 and FREQ0.
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FREQ(pub u32);
+
+impl FREQ {
+    /// The frequency word closest to `hz`.
+    pub fn from_hz(hz: u64) -> Self {
+        let word = ((hz.min(FXOSC << 8) << 16) + FXOSC / 2) / FXOSC;
+        FREQ(word.min(0xFF_FFFF) as u32)
+    }
+
+    /// Base frequency in Hz, `FXOSC / 2^16 * FREQ` rounded down.
+    pub fn hz(&self) -> u64 {
+        (self.0 as u64 * FXOSC) >> 16
+    }
+}
+
+impl Composite for FREQ {
+    const START: Config = Config::FREQ2;
+    const MASKS: &'static [u8] = &[0xFF, 0xFF, 0xFF];
+
+    fn from_bytes(bytes: &[u8]) -> Self {
+        FREQ(u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]))
+    }
+
+    fn to_bytes(&self, bytes: &mut [u8]) {
+        bytes.copy_from_slice(&self.0.to_be_bytes()[1..]);
+    }
+}
+
+/// 16-bit sync word in SYNC1 and SYNC0.
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SYNC(pub u16);
+
+impl Composite for SYNC {
+    const START: Config = Config::SYNC1;
+    const MASKS: &'static [u8] = &[0xFF, 0xFF];
+
+    fn from_bytes(bytes: &[u8]) -> Self {
+        SYNC(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn to_bytes(&self, bytes: &mut [u8]) {
+        bytes.copy_from_slice(&self.0.to_be_bytes());
+    }
+}
+
+/// Event 0 timeout for Wake On Radio in WOREVT1 and WOREVT0.
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WOREVT(pub u16);
+
+impl Composite for WOREVT {
+    const START: Config = Config::WOREVT1;
+    const MASKS: &'static [u8] = &[0xFF, 0xFF];
+
+    fn from_bytes(bytes: &[u8]) -> Self {
+        WOREVT(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn to_bytes(&self, bytes: &mut [u8]) {
+        bytes.copy_from_slice(&self.0.to_be_bytes());
+    }
+}
+
+/// Symbol rate, exponent in MDMCFG4 and mantissa in MDMCFG3.
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DRATE {
+    pub mantissa: u8,
+    pub exponent: u8,
+}
+
+impl DRATE {
+    /// The symbol rate setting closest to `baud`.
+    pub fn from_baud(baud: u64) -> Self {
+        let (mantissa, exponent) = from_drate(baud);
+        DRATE {
+            mantissa,
+            exponent,
+        }
+    }
+
+    /// Symbol rate in baud.
+    pub fn baud(&self) -> u64 {
+        to_drate(self.mantissa, self.exponent)
+    }
+}
+
+impl Composite for DRATE {
+    const START: Config = Config::MDMCFG4;
+    const MASKS: &'static [u8] = &[0x0F, 0xFF];
+
+    fn from_bytes(bytes: &[u8]) -> Self {
+        DRATE {
+            mantissa: bytes[1],
+            exponent: bytes[0] & 0x0F,
+        }
+    }
+
+    fn to_bytes(&self, bytes: &mut [u8]) {
+        bytes[0] = self.exponent & 0x0F;
+        bytes[1] = self.mantissa;
+    }
+}
+
+/// Channel spacing, exponent in MDMCFG1 and mantissa in MDMCFG0.
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CHANSPC {
+    pub mantissa: u8,
+    pub exponent: u8,
+}
+
+impl CHANSPC {
+    /// The channel spacing setting closest to `hz`.
+    pub fn from_hz(hz: u64) -> Self {
+        let (mantissa, exponent) = from_chanspc(hz);
+        CHANSPC {
+            mantissa,
+            exponent,
+        }
+    }
+
+    /// Channel spacing in Hz.
+    pub fn hz(&self) -> u64 {
+        to_chanspc(self.mantissa, self.exponent)
+    }
+}
+
+impl Composite for CHANSPC {
+    const START: Config = Config::MDMCFG1;
+    const MASKS: &'static [u8] = &[0x03, 0xFF];
+
+    fn from_bytes(bytes: &[u8]) -> Self {
+        CHANSPC {
+            mantissa: bytes[1],
+            exponent: bytes[0] & 0x03,
+        }
+    }
+
+    fn to_bytes(&self, bytes: &mut [u8]) {
+        bytes[0] = self.exponent & 0x03;
+        bytes[1] = self.mantissa;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lowlevel::registers::*;
+    use crate::lowlevel::traits::Composite;
+
+    fn bytes<T: Composite>(value: &T) -> [u8; 3] {
+        let mut bytes = [0; 3];
+        value.to_bytes(&mut bytes[..T::MASKS.len()]);
+        bytes
+    }
+
+    #[test]
+    fn test_freq() {
+        let freq = FREQ::from_hz(433_000_000);
+        assert_eq!(freq, FREQ(0x10A762));
+        assert_eq!(freq.hz(), 432_999_816);
+        assert_eq!(FREQ::from_hz(freq.hz()), freq);
+        assert_eq!(bytes(&freq), [0x10, 0xA7, 0x62]);
+        assert_eq!(FREQ::from_bytes(&[0x10, 0xA7, 0x62]), freq);
+
+        // Rounded to the nearest step of 396.7 Hz.
+        assert_eq!(FREQ::from_hz(433_000_400), FREQ(0x10A763));
+        assert_eq!(FREQ::from_hz(868_000_000), FREQ(0x216276));
+        assert_eq!(FREQ::from_hz(0), FREQ(0));
+        assert_eq!(FREQ::from_hz(u64::MAX), FREQ(0xFF_FFFF));
+    }
+
+    #[test]
+    fn test_sync_worevt() {
+        assert_eq!(bytes(&SYNC(0xD391)), [0xD3, 0x91, 0]);
+        assert_eq!(SYNC::from_bytes(&[0xD3, 0x91]), SYNC(0xD391));
+        assert_eq!(WOREVT::from_bytes(&[0x87, 0x6B]), WOREVT(0x876B));
+    }
+
+    #[test]
+    fn test_mantissa_exponent() {
+        // Reset values of MDMCFG4/3 and MDMCFG1/0.
+        let drate = DRATE::from_bytes(&[0x8C, 0x22]);
+        assert_eq!((drate.mantissa, drate.exponent), (0x22, 0x0C));
+        assert_eq!(drate.baud(), 115_051);
+        assert_eq!(bytes(&drate), [0x0C, 0x22, 0]);
+
+        let chanspc = CHANSPC::from_bytes(&[0x22, 0xF8]);
+        assert_eq!((chanspc.mantissa, chanspc.exponent), (0xF8, 0x02));
+        assert_eq!(chanspc.hz(), 199_951);
+        assert_eq!(CHANSPC::from_hz(200_000), chanspc);
+        assert_eq!(bytes(&chanspc), [0x02, 0xF8, 0]);
+    }
+}
@@ -1,3 +1,8 @@
+use super::bytes_eq;
+use crate::lowlevel::traits::R;
+use crate::lowlevel::types::*;
+use crate::lowlevel::Field;
+
 #[derive(Clone, Copy)]
 pub enum Config {
     /// GDO2 output pin configuration
@@ -97,22 +102,246 @@ pub enum Config {
 }
 
 impl Config {
+    /// All configuration registers, in address order.
+    pub const ALL: [Config; 47] = [
+        Config::IOCFG2,
+        Config::IOCFG1,
+        Config::IOCFG0,
+        Config::FIFOTHR,
+        Config::SYNC1,
+        Config::SYNC0,
+        Config::PKTLEN,
+        Config::PKTCTRL1,
+        Config::PKTCTRL0,
+        Config::ADDR,
+        Config::CHANNR,
+        Config::FSCTRL1,
+        Config::FSCTRL0,
+        Config::FREQ2,
+        Config::FREQ1,
+        Config::FREQ0,
+        Config::MDMCFG4,
+        Config::MDMCFG3,
+        Config::MDMCFG2,
+        Config::MDMCFG1,
+        Config::MDMCFG0,
+        Config::DEVIATN,
+        Config::MCSM2,
+        Config::MCSM1,
+        Config::MCSM0,
+        Config::FOCCFG,
+        Config::BSCFG,
+        Config::AGCCTRL2,
+        Config::AGCCTRL1,
+        Config::AGCCTRL0,
+        Config::WOREVT1,
+        Config::WOREVT0,
+        Config::WORCTRL,
+        Config::FREND1,
+        Config::FREND0,
+        Config::FSCAL3,
+        Config::FSCAL2,
+        Config::FSCAL1,
+        Config::FSCAL0,
+        Config::RCCTRL1,
+        Config::RCCTRL0,
+        Config::FSTEST,
+        Config::PTEST,
+        Config::AGCTEST,
+        Config::TEST2,
+        Config::TEST1,
+        Config::TEST0,
+    ];
+
     pub fn addr(&self) -> u8 {
         *self as u8
     }
+
+    /// The configuration register at `addr`.
+    pub fn from_addr(addr: u8) -> Option<Config> {
+        Config::ALL.get(addr as usize).copied()
+    }
+
+    /// Register name as in the datasheet.
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Config::IOCFG2 => "IOCFG2",
+            Config::IOCFG1 => "IOCFG1",
+            Config::IOCFG0 => "IOCFG0",
+            Config::FIFOTHR => "FIFOTHR",
+            Config::SYNC1 => "SYNC1",
+            Config::SYNC0 => "SYNC0",
+            Config::PKTLEN => "PKTLEN",
+            Config::PKTCTRL1 => "PKTCTRL1",
+            Config::PKTCTRL0 => "PKTCTRL0",
+            Config::ADDR => "ADDR",
+            Config::CHANNR => "CHANNR",
+            Config::FSCTRL1 => "FSCTRL1",
+            Config::FSCTRL0 => "FSCTRL0",
+            Config::FREQ2 => "FREQ2",
+            Config::FREQ1 => "FREQ1",
+            Config::FREQ0 => "FREQ0",
+            Config::MDMCFG4 => "MDMCFG4",
+            Config::MDMCFG3 => "MDMCFG3",
+            Config::MDMCFG2 => "MDMCFG2",
+            Config::MDMCFG1 => "MDMCFG1",
+            Config::MDMCFG0 => "MDMCFG0",
+            Config::DEVIATN => "DEVIATN",
+            Config::MCSM2 => "MCSM2",
+            Config::MCSM1 => "MCSM1",
+            Config::MCSM0 => "MCSM0",
+            Config::FOCCFG => "FOCCFG",
+            Config::BSCFG => "BSCFG",
+            Config::AGCCTRL2 => "AGCCTRL2",
+            Config::AGCCTRL1 => "AGCCTRL1",
+            Config::AGCCTRL0 => "AGCCTRL0",
+            Config::WOREVT1 => "WOREVT1",
+            Config::WOREVT0 => "WOREVT0",
+            Config::WORCTRL => "WORCTRL",
+            Config::FREND1 => "FREND1",
+            Config::FREND0 => "FREND0",
+            Config::FSCAL3 => "FSCAL3",
+            Config::FSCAL2 => "FSCAL2",
+            Config::FSCAL1 => "FSCAL1",
+            Config::FSCAL0 => "FSCAL0",
+            Config::RCCTRL1 => "RCCTRL1",
+            Config::RCCTRL0 => "RCCTRL0",
+            Config::FSTEST => "FSTEST",
+            Config::PTEST => "PTEST",
+            Config::AGCTEST => "AGCTEST",
+            Config::TEST2 => "TEST2",
+            Config::TEST1 => "TEST1",
+            Config::TEST0 => "TEST0",
+        }
+    }
+
+    /// The config register named `name`.
+    pub const fn from_name(name: &str) -> Option<Config> {
+        let mut i = 0;
+        while i < Config::ALL.len() {
+            if bytes_eq(Config::ALL[i].name().as_bytes(), name.as_bytes()) {
+                return Some(Config::ALL[i]);
+            }
+            i += 1;
+        }
+        None
+    }
+
+    /// Bitfields of the register, most significant first.
+    pub fn fields(&self) -> &'static [Field] {
+        match self {
+            Config::IOCFG2 => IOCFG2::<R>::FIELDS,
+            Config::IOCFG1 => IOCFG1::<R>::FIELDS,
+            Config::IOCFG0 => IOCFG0::<R>::FIELDS,
+            Config::FIFOTHR => FIFOTHR::<R>::FIELDS,
+            Config::SYNC1 => SYNC1::<R>::FIELDS,
+            Config::SYNC0 => SYNC0::<R>::FIELDS,
+            Config::PKTLEN => PKTLEN::<R>::FIELDS,
+            Config::PKTCTRL1 => PKTCTRL1::<R>::FIELDS,
+            Config::PKTCTRL0 => PKTCTRL0::<R>::FIELDS,
+            Config::ADDR => ADDR::<R>::FIELDS,
+            Config::CHANNR => CHANNR::<R>::FIELDS,
+            Config::FSCTRL1 => FSCTRL1::<R>::FIELDS,
+            Config::FSCTRL0 => FSCTRL0::<R>::FIELDS,
+            Config::FREQ2 => FREQ2::<R>::FIELDS,
+            Config::FREQ1 => FREQ1::<R>::FIELDS,
+            Config::FREQ0 => FREQ0::<R>::FIELDS,
+            Config::MDMCFG4 => MDMCFG4::<R>::FIELDS,
+            Config::MDMCFG3 => MDMCFG3::<R>::FIELDS,
+            Config::MDMCFG2 => MDMCFG2::<R>::FIELDS,
+            Config::MDMCFG1 => MDMCFG1::<R>::FIELDS,
+            Config::MDMCFG0 => MDMCFG0::<R>::FIELDS,
+            Config::DEVIATN => DEVIATN::<R>::FIELDS,
+            Config::MCSM2 => MCSM2::<R>::FIELDS,
+            Config::MCSM1 => MCSM1::<R>::FIELDS,
+            Config::MCSM0 => MCSM0::<R>::FIELDS,
+            Config::FOCCFG => FOCCFG::<R>::FIELDS,
+            Config::BSCFG => BSCFG::<R>::FIELDS,
+            Config::AGCCTRL2 => AGCCTRL2::<R>::FIELDS,
+            Config::AGCCTRL1 => AGCCTRL1::<R>::FIELDS,
+            Config::AGCCTRL0 => AGCCTRL0::<R>::FIELDS,
+            Config::WOREVT1 => WOREVT1::<R>::FIELDS,
+            Config::WOREVT0 => WOREVT0::<R>::FIELDS,
+            Config::WORCTRL => WORCTRL::<R>::FIELDS,
+            Config::FREND1 => FREND1::<R>::FIELDS,
+            Config::FREND0 => FREND0::<R>::FIELDS,
+            Config::FSCAL3 => FSCAL3::<R>::FIELDS,
+            Config::FSCAL2 => FSCAL2::<R>::FIELDS,
+            Config::FSCAL1 => FSCAL1::<R>::FIELDS,
+            Config::FSCAL0 => FSCAL0::<R>::FIELDS,
+            Config::RCCTRL1 => RCCTRL1::<R>::FIELDS,
+            Config::RCCTRL0 => RCCTRL0::<R>::FIELDS,
+            Config::FSTEST => FSTEST::<R>::FIELDS,
+            Config::PTEST => PTEST::<R>::FIELDS,
+            Config::AGCTEST => AGCTEST::<R>::FIELDS,
+            Config::TEST2 => TEST2::<R>::FIELDS,
+            Config::TEST1 => TEST1::<R>::FIELDS,
+            Config::TEST0 => TEST0::<R>::FIELDS,
+        }
+    }
 }
 
-impl Into<crate::lowlevel::registers::Register> for Config {
-    fn into(self) -> crate::lowlevel::registers::Register {
-        crate::lowlevel::registers::Register::Config(self)
+impl From<Config> for crate::lowlevel::registers::Register {
+    fn from(reg: Config) -> Self {
+        crate::lowlevel::registers::Register::Config(reg)
     }
 }
 
+register_addresses!(Config {
+    IOCFG2,
+    IOCFG1,
+    IOCFG0,
+    FIFOTHR,
+    SYNC1,
+    SYNC0,
+    PKTLEN,
+    PKTCTRL1,
+    PKTCTRL0,
+    ADDR,
+    CHANNR,
+    FSCTRL1,
+    FSCTRL0,
+    FREQ2,
+    FREQ1,
+    FREQ0,
+    MDMCFG4,
+    MDMCFG3,
+    MDMCFG2,
+    MDMCFG1,
+    MDMCFG0,
+    DEVIATN,
+    MCSM2,
+    MCSM1,
+    MCSM0,
+    FOCCFG,
+    BSCFG,
+    AGCCTRL2,
+    AGCCTRL1,
+    AGCCTRL0,
+    WOREVT1,
+    WOREVT0,
+    WORCTRL,
+    FREND1,
+    FREND0,
+    FSCAL3,
+    FSCAL2,
+    FSCAL1,
+    FSCAL0,
+    RCCTRL1,
+    RCCTRL0,
+    FSTEST,
+    PTEST,
+    AGCTEST,
+    TEST2,
+    TEST1,
+    TEST0,
+});
+
 register!(IOCFG2, 0b0010_1001, u8, {
     #[doc = "Invert output, i.e. select active low (1) / high (0)"]
     gdo2_inv @ 6,
     #[doc = "Default is CHP_RDYn (See Table 41 on page 62)"]
-    gdo2_cfg @ 0..5,
+    gdo2_cfg @ 0..5: GdoCfg,
 });
 
 register!(IOCFG1, 0b0010_1110, u8, {
@@ -121,7 +350,7 @@ register!(IOCFG1, 0b0010_1110, u8, {
     #[doc = "Invert output, i.e. select active low (1) / high (0)"]
     gdo1_inv @ 6,
     #[doc = "Default is 3-state (See Table 41 on page 62)"]
-    gdo1_cfg @ 0..5,
+    gdo1_cfg @ 0..5: GdoCfg,
 });
 
 register!(IOCFG0, 0b0011_1111, u8, {
@@ -130,7 +359,7 @@ register!(IOCFG0, 0b0011_1111, u8, {
     #[doc = "Invert output, i.e. select active low (1) / high (0)"]
     gdo0_inv @ 6,
     #[doc = "Default is CLK_XOSC/192 (See Table 41 on page 62)."]
-    gdo0_cfg @ 0..5,
+    gdo0_cfg @ 0..5: GdoCfg,
 });
 
 register!(FIFOTHR, 0b0000_0111, u8, {
@@ -139,7 +368,7 @@ register!(FIFOTHR, 0b0000_0111, u8, {
     #[doc = "RX Attenuation, see DN010 for more details"]
     close_in_rx @ 4..5,
     #[doc = "Set the threshold for the TX FIFO and RX FIFO"]
-    fifo_thr @ 0..3,
+    fifo_thr @ 0..3: FifoThreshold,
 });
 
 register!(SYNC1, 0b1101_0011, u8, {
@@ -165,18 +394,18 @@ register!(PKTCTRL1, 0b0000_0100, u8, {
     #[doc = "Append RSSI and LQI to RX payload"]
     append_status @ 2,
     #[doc = "Address check configuration of received packages"]
-    adr_chk @ 0..1,
+    adr_chk @ 0..1: AddressCheck,
 });
 
 register!(PKTCTRL0, 0b0100_0101, u8, {
     #[doc = "Turn data whitening on / off"]
     white_data @ 6,
     #[doc = "Format of RX and TX data"]
-    pkt_format @ 4..5,
+    pkt_format @ 4..5: PktFormat,
     #[doc = "CRC calculation on / off"]
     crc_en @ 2,
     #[doc = "Packet length configuration"]
-    length_config @ 0..1,
+    length_config @ 0..1: LengthConfig,
 });
 
 register!(ADDR, 0b0000_0000, u8, {
@@ -232,18 +461,18 @@ register!(MDMCFG2, 0b0000_0010, u8, {
     #[doc = "Disable digital DC blocking filter before demodulator"]
     dem_dcfilt_off @ 7,
     #[doc = "The modulation format of the radio signal"]
-    mod_format @ 4..6,
+    mod_format @ 4..6: ModFormat,
     #[doc = "Enables Manchester encoding/decoding"]
     manchester_en @ 3,
     #[doc = "Combined sync-word qualifier mode"]
-    sync_mode @ 0..2,
+    sync_mode @ 0..2: SyncCheck,
 });
 
 register!(MDMCFG1, 0b0010_0010, u8, {
     #[doc = "Enable Forward Error Correction"]
     fec_en @ 7,
     #[doc = "Sets the minimum number of preamble bytes to be transmitted"]
-    num_preamble @ 4..6,
+    num_preamble @ 4..6: NumPreamble,
     #[doc = "Exponent of channel spacing"]
     chanspc_e @ 0..1,
 });
@@ -264,26 +493,26 @@ register!(MCSM2, 0b0000_0111, u8, {
     #[doc = "Direct RX termination based on RSSI measurement"]
     rx_time_rssi @ 4,
     #[doc = "When RX_TIME expires, check sync_word (0), or either sync_word/PQI (1)"]
-    rx_time_qual @ 3,
+    rx_time_qual @ 3: RxTimeQual,
     #[doc = "Timeout for sync word search in RX for both WOR mode and normal RX operation."]
-    rx_time @ 0..2,
+    rx_time @ 0..2: RxTime,
 
 });
 
 register!(MCSM1, 0b0011_0000, u8, {
     #[doc = "Selects CCA_MODE; Reflected in CCA signal"]
-    cca_mode @ 4..5,
+    cca_mode @ 4..5: CcaMode,
     #[doc = "Select what should happen when a packet has been received"]
-    rxoff_mode @ 2..3,
+    rxoff_mode @ 2..3: RxOffMode,
     #[doc = "Select what should happen when a packet has been sent"]
-    txoff_mode @ 0..1,
+    txoff_mode @ 0..1: TxOffMode,
 });
 
 register!(MCSM0, 0b0000_0100, u8, {
     #[doc = "Automatically calibrate when going to RX or TX, or back to IDLE"]
-    fs_autocal @ 4..5,
+    fs_autocal @ 4..5: AutoCalibration,
     #[doc = "Programs the number of times the six-bit ripple counter must expire after XOSC has stabilized before CHP_RDYn goes low"]
-    po_timeout @ 2..3,
+    po_timeout @ 2..3: PoTimeout,
     #[doc = "Enables the pin radio control option"]
     pin_ctrl_en @ 1,
     #[doc = "Force the XOSC to stay on in the SLEEP state"]
@@ -294,53 +523,53 @@ register!(FOCCFG, 0b0011_0110, u8, {
     #[doc = "If set, the demodulator freezes the frequency offset compensation and clock recovery feedback loops until the CS signal goes high"]
     foc_bs_cs_gate @ 5,
     #[doc = "The frequency compensation loop gain to be used before a sync word is detected"]
-    foc_pre_k @ 3..4,
+    foc_pre_k @ 3..4: FocPreK,
     #[doc = "The frequency compensation loop gain to be used after a sync word is detected"]
-    foc_post_k @ 2,
+    foc_post_k @ 2: FocPostK,
     #[doc = "The saturation point for the frequency offset compensation algorithm"]
-    foc_limit @ 0..1,
+    foc_limit @ 0..1: FocLimit,
 });
 
 register!(BSCFG, 0b0110_1100, u8, {
     #[doc = "The clock recovery feedback loop integral gain to be used before a sync word is detected"]
-    bs_pre_ki @ 6..7,
+    bs_pre_ki @ 6..7: BsPreKi,
     #[doc = "The clock recovery feedback loop proportional gain to be used before a sync word is detected"]
-    bs_pre_kp @ 4..5,
+    bs_pre_kp @ 4..5: BsPreKp,
     #[doc = "The clock recovery feedback loop integral gain to be used after a sync word is detected"]
-    bs_post_ki @ 3,
+    bs_post_ki @ 3: BsPostKi,
     #[doc = "The clock recovery feedback loop proportional gain to be used after a sync word is detected"]
-    bs_post_kp @ 2,
+    bs_post_kp @ 2: BsPostKp,
     #[doc = "The saturation point for the data rate offset compensation algorithm"]
-    bs_limit @ 0..1,
+    bs_limit @ 0..1: BsLimit,
 });
 
 register!(AGCCTRL2, 0b0000_0011, u8, {
     #[doc = "Reduces the maximum allowable DVGA gain"]
-    max_dvga_gain @ 6..7,
+    max_dvga_gain @ 6..7: MaxDvgaGain,
     #[doc = "Sets the maximum allowable LNA + LNA 2 gain relative to the maximum possible gain"]
-    max_lna_gain @ 3..5,
+    max_lna_gain @ 3..5: MaxLnaGain,
     #[doc = "These bits set the target value for the averaged amplitude from the digital channel filter"]
-    magn_target @ 0..2,
+    magn_target @ 0..2: MagnTarget,
 });
 
 register!(AGCCTRL1, 0b0100_0000, u8, {
     #[doc = "Selects between two different strategies for LNA and LNA 2 gain adjustment"]
-    agc_lna_priority @ 6,
+    agc_lna_priority @ 6: AgcLnaPriority,
     #[doc = "Sets the relative change threshold for asserting carrier sense"]
-    carrier_sense_rel_thr @ 4..5,
+    carrier_sense_rel_thr @ 4..5: CarrierSenseRelThr,
     #[doc = "Sets the absolute RSSI threshold for asserting carrier sense."]
     carrier_sense_abs_thr @ 0..3,
 });
 
 register!(AGCCTRL0, 0b1001_0001, u8, {
     #[doc = "Sets the level of hysteresis on the magnitude deviation"]
-    hyst_level @ 6..7,
+    hyst_level @ 6..7: HystLevel,
     #[doc = "Sets the number of channel filter samples from a gain adjustment has been made until the AGC algorithm starts accumulating new samples"]
-    wait_time @ 4..5,
+    wait_time @ 4..5: WaitTime,
     #[doc = "Control when the AGC gain should be frozen"]
-    agc_freeze @ 2..3,
+    agc_freeze @ 2..3: AgcFreeze,
     #[doc = "Filter length, in relation to MOD_FORMAT"]
-    filter_length @ 0..1,
+    filter_length @ 0..1: FilterLength,
 });
 
 register!(WOREVT1, 0b1000_0111, u8, {
@@ -452,3 +681,80 @@ register!(TEST0, 0b0000_1011, u8, {
     #[doc = "The value to use in this register is given by the SmartRF Studio software"]
     test0_0 @ 0,
 });
+
+#[cfg(test)]
+mod tests {
+    use crate::lowlevel::registers::*;
+    use crate::lowlevel::traits::{Register, R};
+    use crate::lowlevel::types::*;
+    use core::fmt::Write;
+
+    struct Buffer([u8; 128], usize);
+
+    impl Write for Buffer {
+        fn write_str(&mut self, s: &str) -> core::fmt::Result {
+            let end = self.1 + s.len();
+            self.0.get_mut(self.1..end).ok_or(core::fmt::Error)?.copy_from_slice(s.as_bytes());
+            self.1 = end;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_typed_fields() {
+        let mdmcfg2 = MDMCFG2(0x32);
+        assert_eq!(mdmcfg2.mod_format(), Ok(ModFormat::MOD_ASK_OOK));
+        assert_eq!(mdmcfg2.sync_mode(), Ok(SyncCheck::CHECK_16_16));
+        assert_eq!(MDMCFG2(0x20).mod_format(), Err(0x02));
+
+        let bits =
+            mdmcfg2.modify().mod_format(ModFormat::MOD_GFSK).sync_mode(SyncCheck::DISABLED).bits();
+        assert_eq!(bits, 0x10);
+    }
+
+    #[test]
+    fn test_single_bit_fields() {
+        // Single bit fields cover exactly their own bit.
+        assert_eq!(RXBYTES(0x80).rxfifo_overflow(), 1);
+        assert_eq!(RXBYTES(0x7F).rxfifo_overflow(), 0);
+        assert_eq!(RXBYTES(0xFF).num_rxbytes(), 0x7F);
+        assert_eq!(PKTSTATUS(0x10).cca(), 1);
+        assert_eq!(PKTSTATUS(0xEF).cca(), 0);
+        assert_eq!(PKTSTATUS(0x20).cca(), 0);
+
+        assert_eq!(PKTCTRL1(0xFF).modify().append_status(0).bits(), 0xFB);
+        assert_eq!(PKTCTRL1(0x00).modify().append_status(1).bits(), 0x04);
+        assert_eq!(MDMCFG2(0x00).modify().manchester_en(1).bits(), 0x08);
+    }
+
+    #[test]
+    fn test_field_metadata() {
+        let fields = MDMCFG2::<R>::FIELDS;
+        assert_eq!(fields.len(), 4);
+        assert_eq!(fields[1].name, "mod_format");
+        assert_eq!((fields[1].offset, fields[1].width, fields[1].reset), (4, 3, 0));
+        assert_eq!((fields[3].offset, fields[3].width, fields[3].reset), (0, 3, 2));
+        assert_eq!(MDMCFG2::<R>::RESET, 0b0000_0010);
+    }
+
+    #[test]
+    fn test_register_address() {
+        assert_eq!(<MDMCFG2 as Register>::ADDRESS.addr(), 0x12);
+        assert_eq!(<MARCSTATE as Register>::ADDRESS.addr(), 0x35);
+
+        let r = PKTCTRL1::from_bits(0x04);
+        let mut w = r.writer();
+        w.adr_chk(AddressCheck::SELF);
+        assert_eq!(PKTCTRL1::into_bits(w), 0x05);
+    }
+
+    #[test]
+    fn test_debug() {
+        let mut buffer = Buffer([0; 128], 0);
+        write!(buffer, "{:?}", MDMCFG2(0x22)).unwrap();
+        assert_eq!(
+            core::str::from_utf8(&buffer.0[..buffer.1]),
+            Ok("MDMCFG2 { dem_dcfilt_off: 0, mod_format: 2, manchester_en: 0, sync_mode: CHECK_16_16 }")
+        );
+    }
+}
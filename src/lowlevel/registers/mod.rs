@@ -1,8 +1,10 @@
 mod command;
+mod composite;
 mod config;
 mod status;
 
 pub use self::command::*;
+pub use self::composite::*;
 pub use self::config::*;
 pub use self::status::*;
 
@@ -32,3 +34,17 @@ impl Register {
         }
     }
 }
+
+pub(crate) const fn bytes_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
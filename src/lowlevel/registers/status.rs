@@ -1,3 +1,8 @@
+use super::bytes_eq;
+use crate::lowlevel::traits::R;
+use crate::lowlevel::types::*;
+use crate::lowlevel::Field;
+
 #[allow(non_camel_case_types)]
 #[derive(Clone, Copy)]
 pub enum Status {
@@ -32,17 +37,104 @@ pub enum Status {
 }
 
 impl Status {
+    /// All status registers, in address order.
+    pub const ALL: [Status; 14] = [
+        Status::PARTNUM,
+        Status::VERSION,
+        Status::FREQEST,
+        Status::LQI,
+        Status::RSSI,
+        Status::MARCSTATE,
+        Status::WORTIME1,
+        Status::WORTIME0,
+        Status::PKTSTATUS,
+        Status::VCO_VC_DAC,
+        Status::TXBYTES,
+        Status::RXBYTES,
+        Status::RCCTRL1_STATUS,
+        Status::RCCTRL0_STATUS,
+    ];
+
     pub fn addr(&self) -> u8 {
         *self as u8
     }
+
+    /// Register name as in the datasheet.
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Status::PARTNUM => "PARTNUM",
+            Status::VERSION => "VERSION",
+            Status::FREQEST => "FREQEST",
+            Status::LQI => "LQI",
+            Status::RSSI => "RSSI",
+            Status::MARCSTATE => "MARCSTATE",
+            Status::WORTIME1 => "WORTIME1",
+            Status::WORTIME0 => "WORTIME0",
+            Status::PKTSTATUS => "PKTSTATUS",
+            Status::VCO_VC_DAC => "VCO_VC_DAC",
+            Status::TXBYTES => "TXBYTES",
+            Status::RXBYTES => "RXBYTES",
+            Status::RCCTRL1_STATUS => "RCCTRL1_STATUS",
+            Status::RCCTRL0_STATUS => "RCCTRL0_STATUS",
+        }
+    }
+
+    /// The status register named `name`.
+    pub const fn from_name(name: &str) -> Option<Status> {
+        let mut i = 0;
+        while i < Status::ALL.len() {
+            if bytes_eq(Status::ALL[i].name().as_bytes(), name.as_bytes()) {
+                return Some(Status::ALL[i]);
+            }
+            i += 1;
+        }
+        None
+    }
+
+    /// Bitfields of the register, most significant first.
+    pub fn fields(&self) -> &'static [Field] {
+        match self {
+            Status::PARTNUM => PARTNUM::<R>::FIELDS,
+            Status::VERSION => VERSION::<R>::FIELDS,
+            Status::FREQEST => FREQEST::<R>::FIELDS,
+            Status::LQI => LQI::<R>::FIELDS,
+            Status::RSSI => RSSI::<R>::FIELDS,
+            Status::MARCSTATE => MARCSTATE::<R>::FIELDS,
+            Status::WORTIME1 => WORTIME1::<R>::FIELDS,
+            Status::WORTIME0 => WORTIME0::<R>::FIELDS,
+            Status::PKTSTATUS => PKTSTATUS::<R>::FIELDS,
+            Status::VCO_VC_DAC => VCO_VC_DAC::<R>::FIELDS,
+            Status::TXBYTES => TXBYTES::<R>::FIELDS,
+            Status::RXBYTES => RXBYTES::<R>::FIELDS,
+            Status::RCCTRL1_STATUS => RCCTRL1_STATUS::<R>::FIELDS,
+            Status::RCCTRL0_STATUS => RCCTRL0_STATUS::<R>::FIELDS,
+        }
+    }
 }
 
-impl Into<crate::lowlevel::registers::Register> for Status {
-    fn into(self) -> crate::lowlevel::registers::Register {
-        crate::lowlevel::registers::Register::Status(self)
+impl From<Status> for crate::lowlevel::registers::Register {
+    fn from(reg: Status) -> Self {
+        crate::lowlevel::registers::Register::Status(reg)
     }
 }
 
+register_addresses!(Status {
+    PARTNUM,
+    VERSION,
+    FREQEST,
+    LQI,
+    RSSI,
+    MARCSTATE,
+    WORTIME1,
+    WORTIME0,
+    PKTSTATUS,
+    VCO_VC_DAC,
+    TXBYTES,
+    RXBYTES,
+    RCCTRL1_STATUS,
+    RCCTRL0_STATUS,
+});
+
 register!(PARTNUM, 0b0000_0000, u8, {
     #[doc = "Chip part number"]
     partnum @ 0..7,
@@ -72,7 +164,7 @@ register!(RSSI, 0b0000_0000, u8, {
 
 register!(MARCSTATE, 0b0000_0000, u8, {
     #[doc = "Main Radio Control FSM State"]
-    marc_state @ 0..4,
+    marc_state @ 0..4: MachineState,
 });
 
 register!(WORTIME1, 0b0000_0000, u8, {
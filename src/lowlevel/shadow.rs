@@ -0,0 +1,78 @@
+use crate::lowlevel::registers::Config;
+
+/// Last known values of the configuration registers and the PA table.
+pub(crate) struct Shadow {
+    config: [Option<u8>; Config::ALL.len()],
+    pa_table: [Option<u8>; 8],
+}
+
+impl Shadow {
+    pub const fn new() -> Self {
+        Shadow {
+            config: [None; Config::ALL.len()],
+            pa_table: [None; 8],
+        }
+    }
+
+    /// The cached value of a register. The frequency synthesizer calibration registers are
+    /// updated by the chip, and never cached.
+    pub fn get(&self, addr: u8) -> Option<u8> {
+        if volatile(addr) {
+            return None;
+        }
+        *self.config.get(addr as usize)?
+    }
+
+    /// Cached values of consecutive registers from `start`, if all are known.
+    pub fn get_burst(&self, start: u8, bytes: &mut [u8]) -> bool {
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            match self.get(start + i as u8) {
+                Some(value) => *byte = value,
+                None => return false,
+            }
+        }
+        true
+    }
+
+    pub fn set_burst(&mut self, start: u8, bytes: &[u8]) {
+        for (i, &byte) in bytes.iter().enumerate() {
+            if let Some(value) = self.config.get_mut(start as usize + i) {
+                *value = Some(byte);
+            }
+        }
+    }
+
+    /// The cached PA table, if all entries are known.
+    pub fn pa_table(&self) -> Option<[u8; 8]> {
+        let mut pa_table = [0; 8];
+        for (byte, value) in pa_table.iter_mut().zip(self.pa_table.iter()) {
+            *byte = (*value)?;
+        }
+        Some(pa_table)
+    }
+
+    /// Entries written from the start of the PA table.
+    pub fn set_pa_table(&mut self, bytes: &[u8]) {
+        for (value, &byte) in self.pa_table.iter_mut().zip(bytes) {
+            *value = Some(byte);
+        }
+    }
+
+    /// Whether the known values match the register and PA table values read from the chip.
+    pub fn matches(&self, config: &[u8], pa_table: &[u8]) -> bool {
+        let config_matches = config
+            .iter()
+            .enumerate()
+            .all(|(addr, &actual)| self.get(addr as u8).map_or(true, |value| value == actual));
+        let pa_table_matches = self
+            .pa_table
+            .iter()
+            .zip(pa_table)
+            .all(|(value, &actual)| value.map_or(true, |value| value == actual));
+        config_matches && pa_table_matches
+    }
+}
+
+fn volatile(addr: u8) -> bool {
+    (Config::FSCAL3 as u8..=Config::FSCAL0 as u8).contains(&addr)
+}
@@ -0,0 +1,287 @@
+//! Simulated chip behind SPI, for tests.
+
+use core::cell::RefCell;
+use core::convert::Infallible;
+use hal::blocking::delay::DelayUs;
+use hal::blocking::spi::{Transfer, Write};
+use hal::digital::v2::OutputPin;
+
+use crate::lowlevel::registers::{Command, Config, Status, MCSM1, PKTCTRL0, PKTCTRL1};
+use crate::lowlevel::types::{LengthConfig, MachineState, RxOffMode, TxOffMode};
+use crate::lowlevel::Cc1101;
+
+const READ: u8 = 0x80;
+const BURST: u8 = 0x40;
+
+/// Registers of the simulated chip.
+pub(crate) struct Chip {
+    pub config: [u8; Config::ALL.len()],
+    pub status: [u8; 14],
+    pub pa_table: [u8; 8],
+    // Received bytes, and the number read from the RX FIFO.
+    rx_fifo: [u8; 64],
+    rx_len: usize,
+    rx_read: usize,
+    /// Packet received once the radio turns around from TX to RX.
+    reply: Option<([u8; 64], usize)>,
+    /// SPI transaction that fails, counted as `transactions`.
+    pub fail_at: Option<usize>,
+    /// Whether clear channel assessment fails, so that STX in RX is ignored.
+    pub channel_busy: bool,
+    /// Number of SPI transactions, one per CS assertion.
+    pub transactions: usize,
+    // Header byte and number of data bytes of the current transaction.
+    header: Option<u8>,
+    offset: usize,
+}
+
+impl Chip {
+    pub fn new() -> Self {
+        Chip {
+            config: [0; Config::ALL.len()],
+            status: [0; 14],
+            pa_table: [0; 8],
+            rx_fifo: [0; 64],
+            rx_len: 0,
+            rx_read: 0,
+            reply: None,
+            fail_at: None,
+            channel_busy: false,
+            transactions: 0,
+            header: None,
+            offset: 0,
+        }
+    }
+
+    /// Put received bytes in the RX FIFO.
+    pub fn receive(&mut self, bytes: &[u8]) {
+        self.rx_fifo[self.rx_len..][..bytes.len()].copy_from_slice(bytes);
+        self.rx_len += bytes.len();
+    }
+
+    /// Put a received packet in the RX FIFO, followed by RSSI and LQI as configured in
+    /// PKTCTRL1.APPEND_STATUS.
+    pub fn receive_packet(&mut self, bytes: &[u8]) {
+        self.receive(bytes);
+        let pktctrl0 = PKTCTRL0(self.config[Config::PKTCTRL0 as usize]);
+        let pktctrl1 = PKTCTRL1(self.config[Config::PKTCTRL1 as usize]);
+        if pktctrl1.append_status() == 1 && pktctrl0.length_config() != Ok(LengthConfig::INFINITE) {
+            let rssi = self.status[Status::RSSI as usize - 0x30];
+            let lqi = self.status[Status::LQI as usize - 0x30];
+            self.receive(&[rssi, lqi]);
+        }
+    }
+
+    /// Receive the packet `bytes` when the radio goes from TX to RX.
+    pub fn reply(&mut self, bytes: &[u8]) {
+        let mut reply = [0; 64];
+        reply[..bytes.len()].copy_from_slice(bytes);
+        self.reply = Some((reply, bytes.len()));
+    }
+
+    /// Number of bytes in the RX FIFO.
+    pub fn rx_bytes(&self) -> usize {
+        self.rx_len - self.rx_read
+    }
+
+    /// Set the main radio control state, MARCSTATE.
+    pub fn set_state(&mut self, state: MachineState) {
+        self.status[Status::MARCSTATE as usize - 0x30] = state.value();
+    }
+
+    fn state(&self) -> u8 {
+        self.status[Status::MARCSTATE as usize - 0x30]
+    }
+
+    fn strobe(&mut self, com: u8) {
+        let state = match com {
+            c if c == Command::SRES.addr() => {
+                self.config = [0; Config::ALL.len()];
+                MachineState::IDLE
+            }
+            c if c == Command::SIDLE.addr() => MachineState::IDLE,
+            c if c == Command::SRX.addr() => MachineState::RX,
+            c if c == Command::STX.addr() => {
+                if self.state() == MachineState::RX.value() && self.channel_busy {
+                    return;
+                }
+                MachineState::TX
+            }
+            c if c == Command::SFRX.addr() => {
+                self.rx_len = 0;
+                self.rx_read = 0;
+                return;
+            }
+            c if c == Command::SPWD.addr() => MachineState::SLEEP,
+            _ => return,
+        };
+        self.set_state(state);
+    }
+
+    // Reads MARCSTATE. A packet is sent in one read, and received once there are bytes in the
+    // RX FIFO, then the state changes as configured in MCSM1.
+    fn read_state(&mut self) -> u8 {
+        let state = self.state();
+        let mcsm1 = MCSM1(self.config[Config::MCSM1 as usize]);
+        let next = if state == MachineState::TX.value() {
+            match mcsm1.txoff_mode() {
+                Ok(TxOffMode::IDLE) => MachineState::IDLE,
+                Ok(TxOffMode::FSTXON) => MachineState::FSTXON,
+                Ok(TxOffMode::STAY_IN_TX) => MachineState::TX,
+                _ => MachineState::RX,
+            }
+        } else if state == MachineState::RX.value() && self.rx_bytes() > 0 {
+            match mcsm1.rxoff_mode() {
+                Ok(RxOffMode::IDLE) => MachineState::IDLE,
+                Ok(RxOffMode::FSTXON) => MachineState::FSTXON,
+                Ok(RxOffMode::TX) => MachineState::TX,
+                _ => MachineState::RX,
+            }
+        } else {
+            return state;
+        };
+        if state == MachineState::TX.value() && next == MachineState::RX {
+            if let Some((reply, len)) = self.reply.take() {
+                self.receive_packet(&reply[..len]);
+            }
+        }
+        self.set_state(next);
+        state
+    }
+
+    fn byte(&mut self, mosi: u8) -> u8 {
+        let header = match self.header {
+            Some(header) => header,
+            None => {
+                self.header = Some(mosi);
+                self.offset = 0;
+                if mosi & BURST == 0 && (0x30..=0x3D).contains(&(mosi & !READ)) {
+                    self.strobe(mosi & !READ);
+                }
+                return 0x0F;
+            }
+        };
+
+        let addr = (header & 0x3F) as usize;
+        let read = header & READ != 0;
+        let burst = header & BURST != 0;
+        let offset = self.offset;
+        if burst {
+            self.offset += 1;
+        }
+
+        match addr {
+            0x00..=0x2E => {
+                if read {
+                    self.config[addr + offset]
+                } else {
+                    self.config[addr + offset] = mosi;
+                    0x0F
+                }
+            }
+            // Status registers can not be read in bursts.
+            0x30..=0x3D if addr == Status::RXBYTES as usize => self.rx_bytes() as u8,
+            0x30..=0x3D if addr == Status::MARCSTATE as usize => self.read_state(),
+            0x30..=0x3D => self.status[addr - 0x30],
+            0x3E => {
+                let entry = offset % 8;
+                if read {
+                    self.pa_table[entry]
+                } else {
+                    self.pa_table[entry] = mosi;
+                    0x0F
+                }
+            }
+            0x3F if read => {
+                let byte = self.rx_fifo[self.rx_read];
+                self.rx_read = (self.rx_read + 1).min(self.rx_len);
+                byte
+            }
+            _ => 0x0F,
+        }
+    }
+}
+
+/// SPI error injected with [`Chip::fail_at`].
+#[derive(Debug, PartialEq)]
+pub(crate) struct Fault;
+
+pub(crate) struct Spi<'a>(pub &'a RefCell<Chip>);
+
+impl<'a> Transfer<u8> for Spi<'a> {
+    type Error = Fault;
+
+    fn transfer<'w>(&mut self, words: &'w mut [u8]) -> Result<&'w [u8], Fault> {
+        let mut chip = self.0.borrow_mut();
+        if chip.fail_at == Some(chip.transactions) {
+            return Err(Fault);
+        }
+        for word in words.iter_mut() {
+            *word = chip.byte(*word);
+        }
+        Ok(words)
+    }
+}
+
+impl<'a> Write<u8> for Spi<'a> {
+    type Error = Fault;
+
+    fn write(&mut self, words: &[u8]) -> Result<(), Fault> {
+        let mut chip = self.0.borrow_mut();
+        if chip.fail_at == Some(chip.transactions) {
+            return Err(Fault);
+        }
+        for &word in words {
+            chip.byte(word);
+        }
+        Ok(())
+    }
+}
+
+pub(crate) struct Cs<'a>(pub &'a RefCell<Chip>);
+
+impl<'a> OutputPin for Cs<'a> {
+    type Error = Infallible;
+
+    fn set_low(&mut self) -> Result<(), Infallible> {
+        self.0.borrow_mut().transactions += 1;
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Infallible> {
+        self.0.borrow_mut().header = None;
+        Ok(())
+    }
+}
+
+/// Driver connected to the simulated chip.
+pub(crate) fn radio(chip: &RefCell<Chip>) -> Cc1101<Spi<'_>, Cs<'_>> {
+    Cc1101::new(Spi(chip), Cs(chip)).unwrap()
+}
+
+/// High level driver connected to the simulated chip.
+pub(crate) fn cc1101(chip: &RefCell<Chip>) -> crate::Cc1101<Spi<'_>, Cs<'_>> {
+    crate::Cc1101::new(Spi(chip), Cs(chip)).unwrap()
+}
+
+/// Delay that returns immediately.
+pub(crate) struct Delay;
+
+impl DelayUs<u32> for Delay {
+    fn delay_us(&mut self, _us: u32) {}
+}
+
+/// Output pin that ignores its level.
+pub(crate) struct Pin;
+
+impl OutputPin for Pin {
+    type Error = Infallible;
+
+    fn set_low(&mut self) -> Result<(), Infallible> {
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Infallible> {
+        Ok(())
+    }
+}
@@ -1,28 +1,84 @@
-use core::ops::Range;
-
-pub trait OffsetSize {
-    fn offset(self) -> u8;
-    fn size(self) -> u8;
+/// Bitfield of a register.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Field {
+    /// Name of the field, as in the register accessors.
+    pub name: &'static str,
+    /// Position of the least significant bit.
+    pub offset: u8,
+    /// Number of bits.
+    pub width: u8,
+    /// Value of the field after reset.
+    pub reset: u8,
 }
 
-impl OffsetSize for u8 {
-    fn offset(self) -> u8 {
-        self
+impl Field {
+    /// A field covering the bits from `bits[0]` to `bits[1]` (inclusive), or just `bits[0]`, of a
+    /// register with the reset value `reset`.
+    pub(crate) const fn new(name: &'static str, bits: &[u8], reset: u8) -> Field {
+        let width = if bits.len() == 2 {
+            bits[1] - bits[0] + 1
+        } else {
+            1
+        };
+        let offset = bits[0];
+        let mask = ((1u16 << width) - 1) as u8;
+        Field {
+            name,
+            offset,
+            width,
+            reset: (reset >> offset) & mask,
+        }
     }
 
-    fn size(self) -> u8 {
-        1
+    /// Mask of the field, not shifted to its offset.
+    pub const fn mask(&self) -> u8 {
+        ((1u16 << self.width) - 1) as u8
     }
-}
 
-impl OffsetSize for Range<u8> {
-    fn offset(self) -> u8 {
-        self.start
+    /// The value of the field in a register value.
+    pub const fn get(&self, bits: u8) -> u8 {
+        (bits >> self.offset) & self.mask()
     }
+}
 
-    fn size(self) -> u8 {
-        self.end - self.start
-    }
+/// A register type generated by `register!`, tied to its address.
+pub trait Register: Copy {
+    /// Address type, [`Config`](super::registers::Config) for writable registers.
+    type Address: Into<super::registers::Register> + Copy;
+
+    /// The register in write mode.
+    type Writer;
+
+    /// Address of the register.
+    const ADDRESS: Self::Address;
+
+    /// The register with the value `bits`.
+    fn from_bits(bits: u8) -> Self;
+
+    /// A writer starting from the value of the register.
+    fn writer(self) -> Self::Writer;
+
+    /// The value of the writer.
+    fn into_bits(writer: Self::Writer) -> u8;
+}
+
+/// A value split across consecutive configuration registers, read and written with a single
+/// burst access.
+///
+/// Sealed, the values are read into buffers sized for the composites in this crate.
+pub trait Composite: Sized + private::Sealed {
+    /// First register of the value.
+    const START: super::registers::Config;
+
+    /// Bits of each register, from `START`, that belong to the value. Other bits are kept when
+    /// the value is written.
+    const MASKS: &'static [u8];
+
+    /// The value in the register bytes.
+    fn from_bytes(bytes: &[u8]) -> Self;
+
+    /// Set the bits of the value in the register bytes, other bits are cleared.
+    fn to_bytes(&self, bytes: &mut [u8]);
 }
 
 #[derive(Clone, Copy)]
@@ -33,3 +89,15 @@ pub struct R;
 
 #[derive(Clone, Copy)]
 pub struct W;
+
+pub(crate) mod private {
+    use crate::lowlevel::registers::{CHANSPC, DRATE, FREQ, SYNC, WOREVT};
+
+    pub trait Sealed {}
+
+    impl Sealed for FREQ {}
+    impl Sealed for SYNC {}
+    impl Sealed for WOREVT {}
+    impl Sealed for DRATE {}
+    impl Sealed for CHANSPC {}
+}
@@ -1,6 +1,8 @@
+use core::convert::TryFrom;
+
 /// Address check configuration.
 #[allow(non_camel_case_types)]
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum AddressCheck {
     /// No address check.
     DISABLED = 0x00,
@@ -17,3 +19,17 @@ impl AddressCheck {
         *self as u8
     }
 }
+
+impl TryFrom<u8> for AddressCheck {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, u8> {
+        match value {
+            0x00 => Ok(AddressCheck::DISABLED),
+            0x01 => Ok(AddressCheck::SELF),
+            0x02 => Ok(AddressCheck::SELF_LOW_BROADCAST),
+            0x03 => Ok(AddressCheck::SELF_HIGH_LOW_BROADCAST),
+            _ => Err(value),
+        }
+    }
+}
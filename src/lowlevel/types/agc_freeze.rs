@@ -0,0 +1,35 @@
+use core::convert::TryFrom;
+
+/// Control when the AGC gain should be frozen.
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AgcFreeze {
+    /// Normal operation. Always adjust gain when required.
+    NORMAL = 0x00,
+    /// The gain setting is frozen when a sync word has been found.
+    ON_SYNC_WORD = 0x01,
+    /// Manually freeze the analogue gain setting and continue to adjust the digital gain.
+    MANUAL_ANALOG = 0x02,
+    /// Manually freezes both the analogue and the digital gain setting. Used for manually overriding the gain.
+    MANUAL_ALL = 0x03,
+}
+
+impl AgcFreeze {
+    pub fn value(&self) -> u8 {
+        *self as u8
+    }
+}
+
+impl TryFrom<u8> for AgcFreeze {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, u8> {
+        match value {
+            0x00 => Ok(AgcFreeze::NORMAL),
+            0x01 => Ok(AgcFreeze::ON_SYNC_WORD),
+            0x02 => Ok(AgcFreeze::MANUAL_ANALOG),
+            0x03 => Ok(AgcFreeze::MANUAL_ALL),
+            _ => Err(value),
+        }
+    }
+}
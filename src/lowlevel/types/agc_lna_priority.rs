@@ -0,0 +1,29 @@
+use core::convert::TryFrom;
+
+/// Strategy for LNA and LNA 2 gain adjustment.
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AgcLnaPriority {
+    /// The LNA 2 gain is decreased to minimum before decreasing LNA gain.
+    LNA2_FIRST = 0x00,
+    /// The LNA gain is decreased first.
+    LNA_FIRST = 0x01,
+}
+
+impl AgcLnaPriority {
+    pub fn value(&self) -> u8 {
+        *self as u8
+    }
+}
+
+impl TryFrom<u8> for AgcLnaPriority {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, u8> {
+        match value {
+            0x00 => Ok(AgcLnaPriority::LNA2_FIRST),
+            0x01 => Ok(AgcLnaPriority::LNA_FIRST),
+            _ => Err(value),
+        }
+    }
+}
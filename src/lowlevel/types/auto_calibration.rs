@@ -1,6 +1,8 @@
+use core::convert::TryFrom;
+
 /// Configure what state transitions result in auto-calibration.
 #[allow(non_camel_case_types)]
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum AutoCalibration {
     /// Never (manually calibrate using SCAL strobe).
     DISABLED = 0x00,
@@ -17,3 +19,17 @@ impl AutoCalibration {
         *self as u8
     }
 }
+
+impl TryFrom<u8> for AutoCalibration {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, u8> {
+        match value {
+            0x00 => Ok(AutoCalibration::DISABLED),
+            0x01 => Ok(AutoCalibration::FROM_IDLE),
+            0x02 => Ok(AutoCalibration::TO_IDLE),
+            0x03 => Ok(AutoCalibration::TO_IDLE_EVERY_4TH),
+            _ => Err(value),
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use core::convert::TryFrom;
+
+/// Saturation point for the data rate offset compensation algorithm.
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BsLimit {
+    /// ±0 (no data rate offset compensation performed).
+    DISABLED = 0x00,
+    /// ±3.125% data rate offset.
+    OFFSET_3_125 = 0x01,
+    /// ±6.25% data rate offset.
+    OFFSET_6_25 = 0x02,
+    /// ±12.5% data rate offset.
+    OFFSET_12_5 = 0x03,
+}
+
+impl BsLimit {
+    pub fn value(&self) -> u8 {
+        *self as u8
+    }
+}
+
+impl TryFrom<u8> for BsLimit {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, u8> {
+        match value {
+            0x00 => Ok(BsLimit::DISABLED),
+            0x01 => Ok(BsLimit::OFFSET_3_125),
+            0x02 => Ok(BsLimit::OFFSET_6_25),
+            0x03 => Ok(BsLimit::OFFSET_12_5),
+            _ => Err(value),
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use core::convert::TryFrom;
+
+/// Clock recovery feedback loop integral gain to be used after a sync word is detected.
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BsPostKi {
+    /// Same as BS_PRE_KI.
+    SAME_AS_PRE = 0x00,
+    /// K_I/2.
+    KI_HALF = 0x01,
+}
+
+impl BsPostKi {
+    pub fn value(&self) -> u8 {
+        *self as u8
+    }
+}
+
+impl TryFrom<u8> for BsPostKi {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, u8> {
+        match value {
+            0x00 => Ok(BsPostKi::SAME_AS_PRE),
+            0x01 => Ok(BsPostKi::KI_HALF),
+            _ => Err(value),
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use core::convert::TryFrom;
+
+/// Clock recovery feedback loop proportional gain to be used after a sync word is detected.
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BsPostKp {
+    /// Same as BS_PRE_KP.
+    SAME_AS_PRE = 0x00,
+    /// K_P.
+    KP_1 = 0x01,
+}
+
+impl BsPostKp {
+    pub fn value(&self) -> u8 {
+        *self as u8
+    }
+}
+
+impl TryFrom<u8> for BsPostKp {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, u8> {
+        match value {
+            0x00 => Ok(BsPostKp::SAME_AS_PRE),
+            0x01 => Ok(BsPostKp::KP_1),
+            _ => Err(value),
+        }
+    }
+}
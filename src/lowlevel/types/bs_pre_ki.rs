@@ -0,0 +1,35 @@
+use core::convert::TryFrom;
+
+/// Clock recovery feedback loop integral gain to be used before a sync word is detected.
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BsPreKi {
+    /// K_I.
+    KI_1 = 0x00,
+    /// 2K_I.
+    KI_2 = 0x01,
+    /// 3K_I.
+    KI_3 = 0x02,
+    /// 4K_I.
+    KI_4 = 0x03,
+}
+
+impl BsPreKi {
+    pub fn value(&self) -> u8 {
+        *self as u8
+    }
+}
+
+impl TryFrom<u8> for BsPreKi {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, u8> {
+        match value {
+            0x00 => Ok(BsPreKi::KI_1),
+            0x01 => Ok(BsPreKi::KI_2),
+            0x02 => Ok(BsPreKi::KI_3),
+            0x03 => Ok(BsPreKi::KI_4),
+            _ => Err(value),
+        }
+    }
+}
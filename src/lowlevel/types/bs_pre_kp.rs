@@ -0,0 +1,35 @@
+use core::convert::TryFrom;
+
+/// Clock recovery feedback loop proportional gain to be used before a sync word is detected.
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BsPreKp {
+    /// K_P.
+    KP_1 = 0x00,
+    /// 2K_P.
+    KP_2 = 0x01,
+    /// 3K_P.
+    KP_3 = 0x02,
+    /// 4K_P.
+    KP_4 = 0x03,
+}
+
+impl BsPreKp {
+    pub fn value(&self) -> u8 {
+        *self as u8
+    }
+}
+
+impl TryFrom<u8> for BsPreKp {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, u8> {
+        match value {
+            0x00 => Ok(BsPreKp::KP_1),
+            0x01 => Ok(BsPreKp::KP_2),
+            0x02 => Ok(BsPreKp::KP_3),
+            0x03 => Ok(BsPreKp::KP_4),
+            _ => Err(value),
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use core::convert::TryFrom;
+
+/// Relative change threshold for asserting carrier sense.
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CarrierSenseRelThr {
+    /// Relative carrier sense threshold disabled.
+    DISABLED = 0x00,
+    /// 6 dB increase in RSSI value.
+    INCREASE_6DB = 0x01,
+    /// 10 dB increase in RSSI value.
+    INCREASE_10DB = 0x02,
+    /// 14 dB increase in RSSI value.
+    INCREASE_14DB = 0x03,
+}
+
+impl CarrierSenseRelThr {
+    pub fn value(&self) -> u8 {
+        *self as u8
+    }
+}
+
+impl TryFrom<u8> for CarrierSenseRelThr {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, u8> {
+        match value {
+            0x00 => Ok(CarrierSenseRelThr::DISABLED),
+            0x01 => Ok(CarrierSenseRelThr::INCREASE_6DB),
+            0x02 => Ok(CarrierSenseRelThr::INCREASE_10DB),
+            0x03 => Ok(CarrierSenseRelThr::INCREASE_14DB),
+            _ => Err(value),
+        }
+    }
+}
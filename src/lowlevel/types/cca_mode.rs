@@ -0,0 +1,35 @@
+use core::convert::TryFrom;
+
+/// Clear channel indication configuration, reflected in the CCA signal.
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CcaMode {
+    /// Always.
+    ALWAYS = 0x00,
+    /// If RSSI below threshold.
+    RSSI_BELOW_THRESHOLD = 0x01,
+    /// Unless currently receiving a packet.
+    UNLESS_RECEIVING = 0x02,
+    /// If RSSI below threshold unless currently receiving a packet.
+    RSSI_BELOW_THRESHOLD_UNLESS_RECEIVING = 0x03,
+}
+
+impl CcaMode {
+    pub fn value(&self) -> u8 {
+        *self as u8
+    }
+}
+
+impl TryFrom<u8> for CcaMode {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, u8> {
+        match value {
+            0x00 => Ok(CcaMode::ALWAYS),
+            0x01 => Ok(CcaMode::RSSI_BELOW_THRESHOLD),
+            0x02 => Ok(CcaMode::UNLESS_RECEIVING),
+            0x03 => Ok(CcaMode::RSSI_BELOW_THRESHOLD_UNLESS_RECEIVING),
+            _ => Err(value),
+        }
+    }
+}
@@ -1,6 +1,8 @@
+use core::convert::TryFrom;
+
 /// TX FIFO and RX FIFO threshold configuration.
 #[allow(non_camel_case_types)]
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum FifoThreshold {
     /// 61 bytes in TX, 4 bytes in RX.
     TX_61_RX_4 = 0x00,
@@ -41,3 +43,29 @@ impl FifoThreshold {
         *self as u8
     }
 }
+
+impl TryFrom<u8> for FifoThreshold {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, u8> {
+        match value {
+            0x00 => Ok(FifoThreshold::TX_61_RX_4),
+            0x01 => Ok(FifoThreshold::TX_57_RX_8),
+            0x02 => Ok(FifoThreshold::TX_53_RX_12),
+            0x03 => Ok(FifoThreshold::TX_49_RX_16),
+            0x04 => Ok(FifoThreshold::TX_45_RX_20),
+            0x05 => Ok(FifoThreshold::TX_41_RX_24),
+            0x06 => Ok(FifoThreshold::TX_37_RX_28),
+            0x07 => Ok(FifoThreshold::TX_33_RX_32),
+            0x08 => Ok(FifoThreshold::TX_29_RX_36),
+            0x09 => Ok(FifoThreshold::TX_25_RX_40),
+            0x0A => Ok(FifoThreshold::TX_21_RX_44),
+            0x0B => Ok(FifoThreshold::TX_17_RX_48),
+            0x0C => Ok(FifoThreshold::TX_13_RX_52),
+            0x0D => Ok(FifoThreshold::TX_9_RX_56),
+            0x0E => Ok(FifoThreshold::TX_5_RX_60),
+            0x0F => Ok(FifoThreshold::TX_1_RX_64),
+            _ => Err(value),
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use core::convert::TryFrom;
+
+/// Averaging length for the amplitude from the channel filter (2-FSK, 4-FSK, MSK), or the OOK/ASK decision boundary.
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FilterLength {
+    /// 8 samples, 4 dB OOK/ASK decision boundary.
+    SAMPLES_8 = 0x00,
+    /// 16 samples, 8 dB OOK/ASK decision boundary.
+    SAMPLES_16 = 0x01,
+    /// 32 samples, 12 dB OOK/ASK decision boundary.
+    SAMPLES_32 = 0x02,
+    /// 64 samples, 16 dB OOK/ASK decision boundary.
+    SAMPLES_64 = 0x03,
+}
+
+impl FilterLength {
+    pub fn value(&self) -> u8 {
+        *self as u8
+    }
+}
+
+impl TryFrom<u8> for FilterLength {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, u8> {
+        match value {
+            0x00 => Ok(FilterLength::SAMPLES_8),
+            0x01 => Ok(FilterLength::SAMPLES_16),
+            0x02 => Ok(FilterLength::SAMPLES_32),
+            0x03 => Ok(FilterLength::SAMPLES_64),
+            _ => Err(value),
+        }
+    }
+}
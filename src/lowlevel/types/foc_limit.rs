@@ -0,0 +1,35 @@
+use core::convert::TryFrom;
+
+/// Saturation point for the frequency offset compensation algorithm.
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FocLimit {
+    /// ±0 (no frequency offset compensation), required for ASK/OOK.
+    DISABLED = 0x00,
+    /// ±BW_CHAN/8.
+    BW_DIV_8 = 0x01,
+    /// ±BW_CHAN/4.
+    BW_DIV_4 = 0x02,
+    /// ±BW_CHAN/2.
+    BW_DIV_2 = 0x03,
+}
+
+impl FocLimit {
+    pub fn value(&self) -> u8 {
+        *self as u8
+    }
+}
+
+impl TryFrom<u8> for FocLimit {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, u8> {
+        match value {
+            0x00 => Ok(FocLimit::DISABLED),
+            0x01 => Ok(FocLimit::BW_DIV_8),
+            0x02 => Ok(FocLimit::BW_DIV_4),
+            0x03 => Ok(FocLimit::BW_DIV_2),
+            _ => Err(value),
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use core::convert::TryFrom;
+
+/// Frequency compensation loop gain to be used after a sync word is detected.
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FocPostK {
+    /// Same as FOC_PRE_K.
+    SAME_AS_PRE = 0x00,
+    /// K/2.
+    K_HALF = 0x01,
+}
+
+impl FocPostK {
+    pub fn value(&self) -> u8 {
+        *self as u8
+    }
+}
+
+impl TryFrom<u8> for FocPostK {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, u8> {
+        match value {
+            0x00 => Ok(FocPostK::SAME_AS_PRE),
+            0x01 => Ok(FocPostK::K_HALF),
+            _ => Err(value),
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use core::convert::TryFrom;
+
+/// Frequency compensation loop gain to be used before a sync word is detected.
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FocPreK {
+    /// K.
+    K_1 = 0x00,
+    /// 2K.
+    K_2 = 0x01,
+    /// 3K.
+    K_3 = 0x02,
+    /// 4K.
+    K_4 = 0x03,
+}
+
+impl FocPreK {
+    pub fn value(&self) -> u8 {
+        *self as u8
+    }
+}
+
+impl TryFrom<u8> for FocPreK {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, u8> {
+        match value {
+            0x00 => Ok(FocPreK::K_1),
+            0x01 => Ok(FocPreK::K_2),
+            0x02 => Ok(FocPreK::K_3),
+            0x03 => Ok(FocPreK::K_4),
+            _ => Err(value),
+        }
+    }
+}
@@ -1,6 +1,8 @@
+use core::convert::TryFrom;
+
 /// General Purpose Control Pin Configuration.
 #[allow(non_camel_case_types)]
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum GdoCfg {
     /// Associated to the RX FIFO: Asserts when RX FIFO is filled at or above the RX FIFO threshold. De-asserts when RX FIFO is drained below the same threshold.
     RX_FIFO_FILLED = 0x00,
@@ -105,3 +107,58 @@ impl GdoCfg {
         *self as u8
     }
 }
+
+impl TryFrom<u8> for GdoCfg {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, u8> {
+        match value {
+            0x00 => Ok(GdoCfg::RX_FIFO_FILLED),
+            0x01 => Ok(GdoCfg::RX_FIFO_FILLED_END_OF_PKT),
+            0x02 => Ok(GdoCfg::TX_FIFO_FILLED),
+            0x03 => Ok(GdoCfg::TX_FIFO_FULL),
+            0x04 => Ok(GdoCfg::RX_FIFO_OVERFLOW),
+            0x05 => Ok(GdoCfg::TX_FIFO_UNDERFLOW),
+            0x06 => Ok(GdoCfg::SYNC_WORD),
+            0x07 => Ok(GdoCfg::CRC_OK),
+            0x08 => Ok(GdoCfg::PQT_REACHED),
+            0x09 => Ok(GdoCfg::CHANNEL_CLEAR),
+            0x0A => Ok(GdoCfg::PLL_LOCK),
+            0x0B => Ok(GdoCfg::SERIAL_CLOCK),
+            0x0C => Ok(GdoCfg::SERIAL_SYNC_DATA_OUT),
+            0x0D => Ok(GdoCfg::SERIAL_DATA_OUT),
+            0x0E => Ok(GdoCfg::CARRIER_SENSE),
+            0x0F => Ok(GdoCfg::LAST_CRC_OK),
+            0x16 => Ok(GdoCfg::RX_HARD_DATA_1),
+            0x17 => Ok(GdoCfg::RX_HARD_DATA_0),
+            0x1B => Ok(GdoCfg::PA_PD),
+            0x1C => Ok(GdoCfg::LNA_PD),
+            0x1D => Ok(GdoCfg::RX_SYMBOL_TICK),
+            0x24 => Ok(GdoCfg::WOR_EVNT0),
+            0x25 => Ok(GdoCfg::WOR_EVNT1),
+            0x26 => Ok(GdoCfg::CLK_256),
+            0x27 => Ok(GdoCfg::CLK_32k),
+            0x29 => Ok(GdoCfg::CHIP_RDYn),
+            0x2B => Ok(GdoCfg::XOSC_STABLE),
+            0x2E => Ok(GdoCfg::HIGH_IMPEDANCE),
+            0x2F => Ok(GdoCfg::HARDWIRE_TO_0),
+            0x30 => Ok(GdoCfg::CLK_XOSC_1),
+            0x31 => Ok(GdoCfg::CLK_XOSC_1_5),
+            0x32 => Ok(GdoCfg::CLK_XOSC_2),
+            0x33 => Ok(GdoCfg::CLK_XOSC_3),
+            0x34 => Ok(GdoCfg::CLK_XOSC_4),
+            0x35 => Ok(GdoCfg::CLK_XOSC_6),
+            0x36 => Ok(GdoCfg::CLK_XOSC_8),
+            0x37 => Ok(GdoCfg::CLK_XOSC_12),
+            0x38 => Ok(GdoCfg::CLK_XOSC_16),
+            0x39 => Ok(GdoCfg::CLK_XOSC_24),
+            0x3A => Ok(GdoCfg::CLK_XOSC_32),
+            0x3B => Ok(GdoCfg::CLK_XOSC_48),
+            0x3C => Ok(GdoCfg::CLK_XOSC_64),
+            0x3D => Ok(GdoCfg::CLK_XOSC_96),
+            0x3E => Ok(GdoCfg::CLK_XOSC_128),
+            0x3F => Ok(GdoCfg::CLK_XOSC_192),
+            _ => Err(value),
+        }
+    }
+}
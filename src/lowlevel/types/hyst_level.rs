@@ -0,0 +1,35 @@
+use core::convert::TryFrom;
+
+/// Level of hysteresis on the magnitude deviation (internal AGC signal that determine gain changes).
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HystLevel {
+    /// No hysteresis, small symmetric dead zone, high gain.
+    NONE = 0x00,
+    /// Low hysteresis, small asymmetric dead zone, medium gain.
+    LOW = 0x01,
+    /// Medium hysteresis, medium asymmetric dead zone, medium gain.
+    MEDIUM = 0x02,
+    /// Large hysteresis, large asymmetric dead zone, low gain.
+    LARGE = 0x03,
+}
+
+impl HystLevel {
+    pub fn value(&self) -> u8 {
+        *self as u8
+    }
+}
+
+impl TryFrom<u8> for HystLevel {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, u8> {
+        match value {
+            0x00 => Ok(HystLevel::NONE),
+            0x01 => Ok(HystLevel::LOW),
+            0x02 => Ok(HystLevel::MEDIUM),
+            0x03 => Ok(HystLevel::LARGE),
+            _ => Err(value),
+        }
+    }
+}
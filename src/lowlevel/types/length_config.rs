@@ -1,6 +1,8 @@
+use core::convert::TryFrom;
+
 /// Packet length configuration.
 #[allow(non_camel_case_types)]
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum LengthConfig {
     /// Fixed packet length mode. Length configured in PKTLEN register.
     FIXED = 0x00,
@@ -15,3 +17,16 @@ impl LengthConfig {
         *self as u8
     }
 }
+
+impl TryFrom<u8> for LengthConfig {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, u8> {
+        match value {
+            0x00 => Ok(LengthConfig::FIXED),
+            0x01 => Ok(LengthConfig::VARIABLE),
+            0x02 => Ok(LengthConfig::INFINITE),
+            _ => Err(value),
+        }
+    }
+}
@@ -1,6 +1,8 @@
+use core::convert::TryFrom;
+
 /// Radio hardware machine states.
 #[allow(non_camel_case_types)]
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum MachineState {
     SLEEP = 0x00,
     IDLE = 0x01,
@@ -32,3 +34,36 @@ impl MachineState {
         *self as u8
     }
 }
+
+impl TryFrom<u8> for MachineState {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, u8> {
+        match value {
+            0x00 => Ok(MachineState::SLEEP),
+            0x01 => Ok(MachineState::IDLE),
+            0x02 => Ok(MachineState::XOFF),
+            0x03 => Ok(MachineState::VCOON_MC),
+            0x04 => Ok(MachineState::REGON_MC),
+            0x05 => Ok(MachineState::MANCAL),
+            0x06 => Ok(MachineState::VCOON),
+            0x07 => Ok(MachineState::REGON),
+            0x08 => Ok(MachineState::STARTCAL),
+            0x09 => Ok(MachineState::BWBOOST),
+            0x0A => Ok(MachineState::FS_LOCK),
+            0x0B => Ok(MachineState::IFADCON),
+            0x0C => Ok(MachineState::ENDCAL),
+            0x0D => Ok(MachineState::RX),
+            0x0E => Ok(MachineState::RX_END),
+            0x0F => Ok(MachineState::RX_RST),
+            0x10 => Ok(MachineState::TXRX_SWITCH),
+            0x11 => Ok(MachineState::RXFIFO_OVERFLOW),
+            0x12 => Ok(MachineState::FSTXON),
+            0x13 => Ok(MachineState::TX),
+            0x14 => Ok(MachineState::TX_END),
+            0x15 => Ok(MachineState::RXTX_SWITCH),
+            0x16 => Ok(MachineState::TXFIFO_UNDERFLOW),
+            _ => Err(value),
+        }
+    }
+}
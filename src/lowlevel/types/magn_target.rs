@@ -0,0 +1,47 @@
+use core::convert::TryFrom;
+
+/// Target value for the averaged amplitude from the digital channel filter.
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MagnTarget {
+    /// 24 dB.
+    DB_24 = 0x00,
+    /// 27 dB.
+    DB_27 = 0x01,
+    /// 30 dB.
+    DB_30 = 0x02,
+    /// 33 dB.
+    DB_33 = 0x03,
+    /// 36 dB.
+    DB_36 = 0x04,
+    /// 38 dB.
+    DB_38 = 0x05,
+    /// 40 dB.
+    DB_40 = 0x06,
+    /// 42 dB.
+    DB_42 = 0x07,
+}
+
+impl MagnTarget {
+    pub fn value(&self) -> u8 {
+        *self as u8
+    }
+}
+
+impl TryFrom<u8> for MagnTarget {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, u8> {
+        match value {
+            0x00 => Ok(MagnTarget::DB_24),
+            0x01 => Ok(MagnTarget::DB_27),
+            0x02 => Ok(MagnTarget::DB_30),
+            0x03 => Ok(MagnTarget::DB_33),
+            0x04 => Ok(MagnTarget::DB_36),
+            0x05 => Ok(MagnTarget::DB_38),
+            0x06 => Ok(MagnTarget::DB_40),
+            0x07 => Ok(MagnTarget::DB_42),
+            _ => Err(value),
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use core::convert::TryFrom;
+
+/// Reduces the maximum allowable DVGA gain.
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MaxDvgaGain {
+    /// All gain settings can be used.
+    ALL = 0x00,
+    /// The highest gain setting can not be used.
+    EXCLUDE_HIGHEST_1 = 0x01,
+    /// The 2 highest gain settings can not be used.
+    EXCLUDE_HIGHEST_2 = 0x02,
+    /// The 3 highest gain settings can not be used.
+    EXCLUDE_HIGHEST_3 = 0x03,
+}
+
+impl MaxDvgaGain {
+    pub fn value(&self) -> u8 {
+        *self as u8
+    }
+}
+
+impl TryFrom<u8> for MaxDvgaGain {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, u8> {
+        match value {
+            0x00 => Ok(MaxDvgaGain::ALL),
+            0x01 => Ok(MaxDvgaGain::EXCLUDE_HIGHEST_1),
+            0x02 => Ok(MaxDvgaGain::EXCLUDE_HIGHEST_2),
+            0x03 => Ok(MaxDvgaGain::EXCLUDE_HIGHEST_3),
+            _ => Err(value),
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use core::convert::TryFrom;
+
+/// Maximum allowable LNA + LNA 2 gain relative to the maximum possible gain.
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MaxLnaGain {
+    /// Maximum possible LNA + LNA 2 gain.
+    MAX = 0x00,
+    /// Approx. 2.6 dB below maximum possible gain.
+    MINUS_2_6_DB = 0x01,
+    /// Approx. 6.1 dB below maximum possible gain.
+    MINUS_6_1_DB = 0x02,
+    /// Approx. 7.4 dB below maximum possible gain.
+    MINUS_7_4_DB = 0x03,
+    /// Approx. 9.2 dB below maximum possible gain.
+    MINUS_9_2_DB = 0x04,
+    /// Approx. 11.5 dB below maximum possible gain.
+    MINUS_11_5_DB = 0x05,
+    /// Approx. 14.6 dB below maximum possible gain.
+    MINUS_14_6_DB = 0x06,
+    /// Approx. 17.1 dB below maximum possible gain.
+    MINUS_17_1_DB = 0x07,
+}
+
+impl MaxLnaGain {
+    pub fn value(&self) -> u8 {
+        *self as u8
+    }
+}
+
+impl TryFrom<u8> for MaxLnaGain {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, u8> {
+        match value {
+            0x00 => Ok(MaxLnaGain::MAX),
+            0x01 => Ok(MaxLnaGain::MINUS_2_6_DB),
+            0x02 => Ok(MaxLnaGain::MINUS_6_1_DB),
+            0x03 => Ok(MaxLnaGain::MINUS_7_4_DB),
+            0x04 => Ok(MaxLnaGain::MINUS_9_2_DB),
+            0x05 => Ok(MaxLnaGain::MINUS_11_5_DB),
+            0x06 => Ok(MaxLnaGain::MINUS_14_6_DB),
+            0x07 => Ok(MaxLnaGain::MINUS_17_1_DB),
+            _ => Err(value),
+        }
+    }
+}
@@ -1,21 +1,67 @@
 mod address_check;
+mod agc_freeze;
+mod agc_lna_priority;
 mod auto_calibration;
+mod bs_limit;
+mod bs_post_ki;
+mod bs_post_kp;
+mod bs_pre_ki;
+mod bs_pre_kp;
+mod carrier_sense_rel_thr;
+mod cca_mode;
 mod fifo_threshold;
+mod filter_length;
+mod foc_limit;
+mod foc_post_k;
+mod foc_pre_k;
 mod gdo_cfg;
+mod hyst_level;
 mod length_config;
 mod machine_state;
+mod magn_target;
+mod max_dvga_gain;
+mod max_lna_gain;
 mod mod_format;
 mod num_preamble;
+mod pkt_format;
 mod po_timeout;
+mod rx_time;
+mod rx_time_qual;
+mod rxoff_mode;
 mod sync_check;
+mod txoff_mode;
+mod wait_time;
 
 pub use self::address_check::*;
+pub use self::agc_freeze::*;
+pub use self::agc_lna_priority::*;
 pub use self::auto_calibration::*;
+pub use self::bs_limit::*;
+pub use self::bs_post_ki::*;
+pub use self::bs_post_kp::*;
+pub use self::bs_pre_ki::*;
+pub use self::bs_pre_kp::*;
+pub use self::carrier_sense_rel_thr::*;
+pub use self::cca_mode::*;
 pub use self::fifo_threshold::*;
+pub use self::filter_length::*;
+pub use self::foc_limit::*;
+pub use self::foc_post_k::*;
+pub use self::foc_pre_k::*;
 pub use self::gdo_cfg::*;
+pub use self::hyst_level::*;
 pub use self::length_config::*;
 pub use self::machine_state::*;
+pub use self::magn_target::*;
+pub use self::max_dvga_gain::*;
+pub use self::max_lna_gain::*;
 pub use self::mod_format::*;
 pub use self::num_preamble::*;
+pub use self::pkt_format::*;
 pub use self::po_timeout::*;
+pub use self::rx_time::*;
+pub use self::rx_time_qual::*;
+pub use self::rxoff_mode::*;
 pub use self::sync_check::*;
+pub use self::txoff_mode::*;
+pub use self::wait_time::*;
@@ -1,6 +1,8 @@
+use core::convert::TryFrom;
+
 /// Modulation format configuration.
 #[allow(non_camel_case_types)]
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum ModFormat {
     /// 2-FSK.
     MOD_2FSK = 0x00,
@@ -19,3 +21,18 @@ impl ModFormat {
         self as u8
     }
 }
+
+impl TryFrom<u8> for ModFormat {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, u8> {
+        match value {
+            0x00 => Ok(ModFormat::MOD_2FSK),
+            0x01 => Ok(ModFormat::MOD_GFSK),
+            0x03 => Ok(ModFormat::MOD_ASK_OOK),
+            0x04 => Ok(ModFormat::MOD_4FSK),
+            0x07 => Ok(ModFormat::MOD_MSK),
+            _ => Err(value),
+        }
+    }
+}
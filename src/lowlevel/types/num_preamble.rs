@@ -1,6 +1,8 @@
+use core::convert::TryFrom;
+
 /// Number of preamble bytes to be transmitted.
 #[allow(non_camel_case_types)]
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum NumPreamble {
     N_2 = 0x00,
     N_3 = 0x01,
@@ -17,3 +19,21 @@ impl NumPreamble {
         *self as u8
     }
 }
+
+impl TryFrom<u8> for NumPreamble {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, u8> {
+        match value {
+            0x00 => Ok(NumPreamble::N_2),
+            0x01 => Ok(NumPreamble::N_3),
+            0x02 => Ok(NumPreamble::N_4),
+            0x03 => Ok(NumPreamble::N_6),
+            0x04 => Ok(NumPreamble::N_8),
+            0x05 => Ok(NumPreamble::N_12),
+            0x06 => Ok(NumPreamble::N_16),
+            0x07 => Ok(NumPreamble::N_24),
+            _ => Err(value),
+        }
+    }
+}
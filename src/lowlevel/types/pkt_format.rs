@@ -0,0 +1,35 @@
+use core::convert::TryFrom;
+
+/// Format of RX and TX data.
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PktFormat {
+    /// Normal mode, use FIFOs for RX and TX.
+    NORMAL = 0x00,
+    /// Synchronous serial mode, used for backwards compatibility. Data in on GDO0.
+    SYNCHRONOUS_SERIAL = 0x01,
+    /// Random TX mode; sends random data using PN9 generator. Used for test. Works as normal mode in RX.
+    RANDOM_TX = 0x02,
+    /// Asynchronous serial mode. Data in on GDO0 and data out on either of the GDOx pins.
+    ASYNCHRONOUS_SERIAL = 0x03,
+}
+
+impl PktFormat {
+    pub fn value(&self) -> u8 {
+        *self as u8
+    }
+}
+
+impl TryFrom<u8> for PktFormat {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, u8> {
+        match value {
+            0x00 => Ok(PktFormat::NORMAL),
+            0x01 => Ok(PktFormat::SYNCHRONOUS_SERIAL),
+            0x02 => Ok(PktFormat::RANDOM_TX),
+            0x03 => Ok(PktFormat::ASYNCHRONOUS_SERIAL),
+            _ => Err(value),
+        }
+    }
+}
@@ -1,6 +1,8 @@
+use core::convert::TryFrom;
+
 /// Programs the number of times the six-bit ripple counter must expire after XOSC has stabilized before CHP_RDYn goes low.
 #[allow(non_camel_case_types)]
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum PoTimeout {
     /// Approx. 2.3 – 2.4 μs.
     EXPIRE_COUNT_1 = 0x00,
@@ -17,3 +19,17 @@ impl PoTimeout {
         *self as u8
     }
 }
+
+impl TryFrom<u8> for PoTimeout {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, u8> {
+        match value {
+            0x00 => Ok(PoTimeout::EXPIRE_COUNT_1),
+            0x01 => Ok(PoTimeout::EXPIRE_COUNT_16),
+            0x02 => Ok(PoTimeout::EXPIRE_COUNT_64),
+            0x03 => Ok(PoTimeout::EXPIRE_COUNT_256),
+            _ => Err(value),
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use core::convert::TryFrom;
+
+/// Timeout for sync word search in RX, relative to EVENT0 and WOR_RES (see section 19.5).
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RxTime {
+    /// Longest timeout, 3.6058 ms × EVENT0 when WOR_RES is 0.
+    MAX = 0x00,
+    /// Half of the longest timeout.
+    DIV_2 = 0x01,
+    /// Quarter of the longest timeout.
+    DIV_4 = 0x02,
+    /// 1/8 of the longest timeout.
+    DIV_8 = 0x03,
+    /// 1/16 of the longest timeout.
+    DIV_16 = 0x04,
+    /// 1/32 of the longest timeout.
+    DIV_32 = 0x05,
+    /// 1/64 of the longest timeout.
+    DIV_64 = 0x06,
+    /// No timeout, RX continues until end of packet.
+    NONE = 0x07,
+}
+
+impl RxTime {
+    pub fn value(&self) -> u8 {
+        *self as u8
+    }
+}
+
+impl TryFrom<u8> for RxTime {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, u8> {
+        match value {
+            0x00 => Ok(RxTime::MAX),
+            0x01 => Ok(RxTime::DIV_2),
+            0x02 => Ok(RxTime::DIV_4),
+            0x03 => Ok(RxTime::DIV_8),
+            0x04 => Ok(RxTime::DIV_16),
+            0x05 => Ok(RxTime::DIV_32),
+            0x06 => Ok(RxTime::DIV_64),
+            0x07 => Ok(RxTime::NONE),
+            _ => Err(value),
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use core::convert::TryFrom;
+
+/// Condition to continue RX when the RX_TIME timer expires.
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RxTimeQual {
+    /// Continue receive if sync word has been found.
+    SYNC_WORD = 0x00,
+    /// Continue receive if sync word has been found, or if PQI is set, or if PQT is reached.
+    SYNC_WORD_OR_PQI = 0x01,
+}
+
+impl RxTimeQual {
+    pub fn value(&self) -> u8 {
+        *self as u8
+    }
+}
+
+impl TryFrom<u8> for RxTimeQual {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, u8> {
+        match value {
+            0x00 => Ok(RxTimeQual::SYNC_WORD),
+            0x01 => Ok(RxTimeQual::SYNC_WORD_OR_PQI),
+            _ => Err(value),
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use core::convert::TryFrom;
+
+/// State to enter when a packet has been received.
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RxOffMode {
+    /// IDLE.
+    IDLE = 0x00,
+    /// FSTXON.
+    FSTXON = 0x01,
+    /// TX.
+    TX = 0x02,
+    /// Stay in RX.
+    STAY_IN_RX = 0x03,
+}
+
+impl RxOffMode {
+    pub fn value(&self) -> u8 {
+        *self as u8
+    }
+}
+
+impl TryFrom<u8> for RxOffMode {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, u8> {
+        match value {
+            0x00 => Ok(RxOffMode::IDLE),
+            0x01 => Ok(RxOffMode::FSTXON),
+            0x02 => Ok(RxOffMode::TX),
+            0x03 => Ok(RxOffMode::STAY_IN_RX),
+            _ => Err(value),
+        }
+    }
+}
@@ -1,6 +1,8 @@
+use core::convert::TryFrom;
+
 /// Sync word qualifier mode configuration.
 #[allow(non_camel_case_types)]
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum SyncCheck {
     /// No preamble/sync.
     DISABLED = 0x00,
@@ -25,3 +27,21 @@ impl SyncCheck {
         *self as u8
     }
 }
+
+impl TryFrom<u8> for SyncCheck {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, u8> {
+        match value {
+            0x00 => Ok(SyncCheck::DISABLED),
+            0x01 => Ok(SyncCheck::CHECK_15_16),
+            0x02 => Ok(SyncCheck::CHECK_16_16),
+            0x03 => Ok(SyncCheck::CHECK_30_32),
+            0x04 => Ok(SyncCheck::CHECK_0_0_CS),
+            0x05 => Ok(SyncCheck::CHECK_15_16_CS),
+            0x06 => Ok(SyncCheck::CHECK_16_16_CS),
+            0x07 => Ok(SyncCheck::CHECK_30_32_CS),
+            _ => Err(value),
+        }
+    }
+}
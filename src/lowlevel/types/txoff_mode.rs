@@ -0,0 +1,35 @@
+use core::convert::TryFrom;
+
+/// State to enter when a packet has been sent.
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TxOffMode {
+    /// IDLE.
+    IDLE = 0x00,
+    /// FSTXON.
+    FSTXON = 0x01,
+    /// Stay in TX (start sending preamble).
+    STAY_IN_TX = 0x02,
+    /// RX.
+    RX = 0x03,
+}
+
+impl TxOffMode {
+    pub fn value(&self) -> u8 {
+        *self as u8
+    }
+}
+
+impl TryFrom<u8> for TxOffMode {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, u8> {
+        match value {
+            0x00 => Ok(TxOffMode::IDLE),
+            0x01 => Ok(TxOffMode::FSTXON),
+            0x02 => Ok(TxOffMode::STAY_IN_TX),
+            0x03 => Ok(TxOffMode::RX),
+            _ => Err(value),
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use core::convert::TryFrom;
+
+/// Number of channel filter samples from a gain adjustment until the AGC starts accumulating new samples.
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WaitTime {
+    /// 8 samples.
+    SAMPLES_8 = 0x00,
+    /// 16 samples.
+    SAMPLES_16 = 0x01,
+    /// 24 samples.
+    SAMPLES_24 = 0x02,
+    /// 32 samples.
+    SAMPLES_32 = 0x03,
+}
+
+impl WaitTime {
+    pub fn value(&self) -> u8 {
+        *self as u8
+    }
+}
+
+impl TryFrom<u8> for WaitTime {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, u8> {
+        match value {
+            0x00 => Ok(WaitTime::SAMPLES_8),
+            0x01 => Ok(WaitTime::SAMPLES_16),
+            0x02 => Ok(WaitTime::SAMPLES_24),
+            0x03 => Ok(WaitTime::SAMPLES_32),
+            _ => Err(value),
+        }
+    }
+}
@@ -0,0 +1,326 @@
+use hal::digital::v2::InputPin;
+
+use super::{Clock, Pulse};
+
+/// Pulse capture configuration.
+#[derive(Clone, Copy, Debug)]
+pub struct CaptureConfig {
+    /// Pulses shorter than this are treated as noise and merged into the surrounding pulse.
+    pub glitch_us: u32,
+    /// A low period at least this long ends the frame. A high period this long, such as a
+    /// constant carrier, also ends it.
+    pub gap_us: u32,
+}
+
+impl Default for CaptureConfig {
+    /// Suitable for typical 433 MHz remotes and sensors, with symbols of 100 µs and up.
+    fn default() -> Self {
+        CaptureConfig {
+            glitch_us: 50,
+            gap_us: 10_000,
+        }
+    }
+}
+
+/// Capture errors.
+#[derive(Debug)]
+pub enum CaptureError<E> {
+    /// No frame started before the timeout expired.
+    Timeout,
+    /// Platform-dependent GPIO-errors, such as IO errors.
+    Gpio(E),
+}
+
+/// Builds a frame of pulses from level changes of the data pin.
+///
+/// Can be fed from an edge interrupt with [`PulseRecorder::edge`] and checked for the end of
+/// the frame with [`PulseRecorder::poll`], or driven by polling the pin with [`capture`].
+///
+/// A frame starts at the first high level and ends with the first low period of at least
+/// `gap_us`, which is recorded as the last pulse, or when the buffer is full. A high period of
+/// `gap_us` is cut off there and recorded as the last pulse, so a stuck pin ends the frame too.
+pub struct PulseRecorder<'a> {
+    pulses: &'a mut [Pulse],
+    len: usize,
+    config: CaptureConfig,
+    // Level and start time of the pulse in progress, None while waiting for the frame to start.
+    current: Option<(bool, u32)>,
+    complete: bool,
+}
+
+impl<'a> PulseRecorder<'a> {
+    pub fn new(pulses: &'a mut [Pulse], config: CaptureConfig) -> Self {
+        PulseRecorder {
+            pulses,
+            len: 0,
+            config,
+            current: None,
+            complete: false,
+        }
+    }
+
+    /// Record the level of the pin at time `now_us`, calls without a level change are ignored.
+    pub fn edge(&mut self, level: bool, now_us: u32) {
+        if self.complete {
+            return;
+        }
+        match self.current {
+            None => {
+                if level {
+                    self.current = Some((true, now_us));
+                }
+            }
+            Some((current, start)) if current != level => {
+                let duration_us = now_us.wrapping_sub(start);
+                if duration_us >= self.config.glitch_us {
+                    self.push(Pulse {
+                        level: current,
+                        duration_us,
+                    });
+                    self.current = Some((level, now_us));
+                } else if self.len > 0 {
+                    // Merge the glitch into the pulse before it, which continues.
+                    self.len -= 1;
+                    let previous = self.pulses[self.len];
+                    self.current = Some((previous.level, start.wrapping_sub(previous.duration_us)));
+                } else {
+                    // The frame did not really start.
+                    self.current = None;
+                }
+            }
+            Some(_) => {}
+        }
+    }
+
+    /// Check for the end of the frame at time `now_us`, returns whether the frame is complete.
+    pub fn poll(&mut self, now_us: u32) -> bool {
+        if let Some((level, start)) = self.current {
+            let duration_us = now_us.wrapping_sub(start);
+            if duration_us >= self.config.gap_us {
+                self.push(Pulse {
+                    level,
+                    duration_us,
+                });
+                self.current = None;
+                self.complete = true;
+            }
+        }
+        self.complete
+    }
+
+    /// Whether a frame has started.
+    pub fn is_started(&self) -> bool {
+        self.current.is_some() || self.len > 0
+    }
+
+    /// Whether the frame ended, or the buffer is full.
+    pub fn is_complete(&self) -> bool {
+        self.complete
+    }
+
+    /// The pulses recorded so far.
+    pub fn pulses(&self) -> &[Pulse] {
+        &self.pulses[..self.len]
+    }
+
+    /// Discard the recorded pulses and wait for the next frame.
+    pub fn reset(&mut self) {
+        self.len = 0;
+        self.current = None;
+        self.complete = false;
+    }
+
+    fn push(&mut self, pulse: Pulse) {
+        if self.len < self.pulses.len() {
+            self.pulses[self.len] = pulse;
+            self.len += 1;
+        }
+        if self.len == self.pulses.len() {
+            self.complete = true;
+        }
+    }
+}
+
+/// Capture a frame by polling `pin`, the GDO pin configured for serial data output.
+///
+/// The radio should be in RX in asynchronous serial mode, see
+/// [`Cc1101::set_data_mode`](crate::Cc1101::set_data_mode). Returns the number of pulses
+/// recorded, or a timeout if no frame started within `timeout_us`.
+pub fn capture<P, C>(
+    pin: &P,
+    clock: &mut C,
+    recorder: &mut PulseRecorder,
+    timeout_us: u32,
+) -> Result<usize, CaptureError<P::Error>>
+where
+    P: InputPin,
+    C: Clock,
+{
+    let begin = clock.now_us();
+    loop {
+        let level = pin.is_high().map_err(CaptureError::Gpio)?;
+        let now = clock.now_us();
+        recorder.edge(level, now);
+        if recorder.poll(now) {
+            return Ok(recorder.pulses().len());
+        }
+        if !recorder.is_started() && now.wrapping_sub(begin) >= timeout_us {
+            return Err(CaptureError::Timeout);
+        }
+    }
+}
+
+/// Iterator over the frames in a buffer of pulses, see [`split_frames`].
+pub struct Frames<'a> {
+    pulses: &'a [Pulse],
+    gap_us: u32,
+}
+
+/// Split a long recording into frames, ending each frame after a low pulse of at least
+/// `gap_us`. Leading low pulses are skipped.
+pub fn split_frames(pulses: &[Pulse], gap_us: u32) -> Frames<'_> {
+    Frames {
+        pulses,
+        gap_us,
+    }
+}
+
+impl<'a> Iterator for Frames<'a> {
+    type Item = &'a [Pulse];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = self.pulses.iter().position(|p| p.level)?;
+        let pulses = &self.pulses[start..];
+        let end = pulses
+            .iter()
+            .position(|p| !p.level && p.duration_us >= self.gap_us)
+            .map_or(pulses.len(), |i| i + 1);
+        let (frame, rest) = pulses.split_at(end);
+        self.pulses = rest;
+        Some(frame)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ook::*;
+    use hal::digital::v2::InputPin;
+
+    fn pulse(level: bool, duration_us: u32) -> Pulse {
+        Pulse {
+            level,
+            duration_us,
+        }
+    }
+
+    fn record<'a>(buf: &'a mut [Pulse], edges: &[(bool, u32)]) -> PulseRecorder<'a> {
+        let mut recorder = PulseRecorder::new(buf, CaptureConfig::default());
+        for &(level, now) in edges.iter() {
+            recorder.edge(level, now);
+            recorder.poll(now);
+        }
+        recorder
+    }
+
+    #[test]
+    fn test_recorder() {
+        let mut buf = [pulse(false, 0); 8];
+        let recorder = record(
+            &mut buf,
+            &[(false, 0), (true, 1000), (false, 1500), (true, 2500), (false, 3500), (false, 20000)],
+        );
+        assert!(recorder.is_complete());
+        assert_eq!(
+            recorder.pulses(),
+            [pulse(true, 500), pulse(false, 1000), pulse(true, 1000), pulse(false, 16500)]
+        );
+    }
+
+    #[test]
+    fn test_recorder_glitch() {
+        let mut buf = [pulse(false, 0); 8];
+        // Glitches at the start of the frame, within a high and within a low pulse.
+        let recorder = record(
+            &mut buf,
+            &[
+                (true, 0),
+                (false, 10),
+                (true, 1000),
+                (false, 1200),
+                (true, 1220),
+                (false, 1500),
+                (true, 1800),
+                (false, 1810),
+                (true, 2500),
+                (false, 3000),
+            ],
+        );
+        assert!(!recorder.is_complete());
+        assert_eq!(recorder.pulses(), [pulse(true, 500), pulse(false, 1000), pulse(true, 500)]);
+    }
+
+    #[test]
+    fn test_recorder_full() {
+        let mut buf = [pulse(false, 0); 2];
+        let mut recorder = record(&mut buf, &[(true, 0), (false, 100), (true, 200), (false, 300)]);
+        assert!(recorder.is_complete());
+        assert_eq!(recorder.pulses(), [pulse(true, 100), pulse(false, 100)]);
+
+        recorder.reset();
+        assert!(!recorder.is_started());
+        assert_eq!(recorder.pulses(), []);
+    }
+
+    struct Pin(bool);
+
+    impl InputPin for Pin {
+        type Error = core::convert::Infallible;
+
+        fn is_high(&self) -> Result<bool, Self::Error> {
+            Ok(self.0)
+        }
+
+        fn is_low(&self) -> Result<bool, Self::Error> {
+            Ok(!self.0)
+        }
+    }
+
+    struct Ticks(u32);
+
+    impl Clock for Ticks {
+        fn now_us(&mut self) -> u32 {
+            self.0 += 100;
+            self.0
+        }
+    }
+
+    #[test]
+    fn test_capture_stuck_high() {
+        let mut buf = [pulse(false, 0); 8];
+        let mut recorder = PulseRecorder::new(&mut buf, CaptureConfig::default());
+        let pulses = capture(&Pin(true), &mut Ticks(0), &mut recorder, 1_000).unwrap();
+        assert_eq!(pulses, 1);
+        assert_eq!(recorder.pulses(), [pulse(true, 10_000)]);
+
+        recorder.reset();
+        let timeout = capture(&Pin(false), &mut Ticks(0), &mut recorder, 1_000);
+        assert!(matches!(timeout, Err(CaptureError::Timeout)));
+    }
+
+    #[test]
+    fn test_split_frames() {
+        let pulses = [
+            pulse(false, 50000),
+            pulse(true, 500),
+            pulse(false, 1000),
+            pulse(true, 500),
+            pulse(false, 12000),
+            pulse(true, 300),
+            pulse(false, 600),
+        ];
+        let mut frames = split_frames(&pulses, 10_000);
+        assert_eq!(frames.next(), Some(&pulses[1..5]));
+        assert_eq!(frames.next(), Some(&pulses[5..]));
+        assert_eq!(frames.next(), None);
+    }
+}
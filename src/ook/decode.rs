@@ -0,0 +1,562 @@
+use super::Pulse;
+
+/// Maximum number of bits in a [`BitRow`].
+pub const MAX_ROW_BITS: usize = 256;
+
+/// Maximum number of rows in [`BitRows`].
+pub const MAX_ROWS: usize = 8;
+
+/// Line coding of an OOK signal.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LineCoding {
+    /// Pulse width modulation, a short pulse is 1 and a long pulse is 0. `short_us` and
+    /// `long_us` are pulse widths.
+    Pwm,
+    /// Pulse position (distance) modulation, a short gap is 0 and a long gap is 1. `short_us`
+    /// and `long_us` are gap widths.
+    Ppm,
+    /// Manchester, 1 is high then low and 0 is low then high. `short_us` is half a bit and
+    /// `long_us` a whole bit. The first bit is assumed to be 0, its low half is part of the
+    /// silence before the frame.
+    Manchester,
+    /// Differential Manchester, a level change in the middle of a bit is 0 and no change is 1.
+    /// `short_us` is half a bit and `long_us` a whole bit.
+    DifferentialManchester,
+    /// Non return to zero, each `short_us` of a pulse is one bit of its level.
+    Nrz,
+    /// Return to zero pulse code modulation, a pulse of `short_us` is 1 and each `long_us` bit
+    /// period without a pulse is 0.
+    Pcm,
+}
+
+/// Pulse timing for a [`LineCoding`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Timing {
+    pub short_us: u32,
+    pub long_us: u32,
+    /// A gap at least this long ends the row, 0 to disable.
+    pub gap_us: u32,
+    /// Maximum difference from `short_us` or `long_us` for a pulse to be accepted. Pulses not
+    /// matching either end the row.
+    pub tolerance_us: u32,
+}
+
+impl Timing {
+    /// Estimate short and long widths from the pulses of the given level, or both levels if
+    /// `None`, shorter than `gap_us`.
+    ///
+    /// The widths are split into two groups, with the tolerance half-way between them. If all
+    /// widths are within 50% of each other they are considered one group, returned as both short
+    /// and long with a tolerance of 25%.
+    pub fn estimate(pulses: &[Pulse], level: Option<bool>, gap_us: u32) -> Option<Timing> {
+        let widths = || {
+            pulses
+                .iter()
+                .filter(move |p| level.map_or(true, |level| p.level == level))
+                .filter(move |p| gap_us == 0 || p.duration_us < gap_us)
+                .map(|p| p.duration_us)
+        };
+
+        let min = widths().min()?;
+        let max = widths().max()?;
+
+        if 2 * max as u64 <= 3 * min as u64 {
+            let average = mean(widths())?;
+            return Some(Timing {
+                short_us: average,
+                long_us: average,
+                gap_us,
+                tolerance_us: average / 4,
+            });
+        }
+
+        let (mut short_us, mut long_us) = (min, max);
+        for _ in 0..4 {
+            let threshold = short_us + (long_us - short_us) / 2;
+            short_us = mean(widths().filter(|&w| w <= threshold))?;
+            long_us = mean(widths().filter(|&w| w > threshold))?;
+        }
+        Some(Timing {
+            short_us,
+            long_us,
+            gap_us,
+            tolerance_us: (long_us - short_us) / 2,
+        })
+    }
+
+    fn is_gap(&self, pulse: &Pulse) -> bool {
+        !pulse.level && self.gap_us > 0 && pulse.duration_us >= self.gap_us
+    }
+
+    fn classify(&self, duration_us: u32) -> Option<Width> {
+        if duration_us.abs_diff(self.short_us) <= self.tolerance_us {
+            Some(Width::Short)
+        } else if duration_us.abs_diff(self.long_us) <= self.tolerance_us {
+            Some(Width::Long)
+        } else {
+            None
+        }
+    }
+}
+
+fn mean<I: Iterator<Item = u32>>(widths: I) -> Option<u32> {
+    let (sum, count) = widths.fold((0u64, 0u64), |(sum, count), w| (sum + w as u64, count + 1));
+    sum.checked_div(count).map(|mean| mean as u32)
+}
+
+enum Width {
+    Short,
+    Long,
+}
+
+/// A row of decoded bits, most significant bit first.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BitRow {
+    bytes: [u8; MAX_ROW_BITS / 8],
+    len: usize,
+}
+
+impl BitRow {
+    const EMPTY: BitRow = BitRow {
+        bytes: [0; MAX_ROW_BITS / 8],
+        len: 0,
+    };
+
+    /// Number of bits.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The bits, the last byte is padded with zeros.
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes[..(self.len + 7) / 8]
+    }
+
+    /// Bit at `index`.
+    pub fn bit(&self, index: usize) -> bool {
+        index < self.len && self.bytes[index / 8] & (0x80 >> (index % 8)) != 0
+    }
+
+    fn push(&mut self, bit: bool) {
+        if self.len < MAX_ROW_BITS {
+            if bit {
+                self.bytes[self.len / 8] |= 0x80 >> (self.len % 8);
+            }
+            self.len += 1;
+        }
+    }
+}
+
+/// Rows of decoded bits, split at gaps and invalid pulses.
+///
+/// Bits beyond [`MAX_ROW_BITS`] and rows beyond [`MAX_ROWS`] are dropped.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BitRows {
+    rows: [BitRow; MAX_ROWS],
+    len: usize,
+    // Whether bits are added to the last row, or start a new one.
+    open: bool,
+}
+
+impl BitRows {
+    fn new() -> Self {
+        BitRows {
+            rows: [BitRow::EMPTY; MAX_ROWS],
+            len: 0,
+            open: false,
+        }
+    }
+
+    /// The rows, none of them empty.
+    pub fn rows(&self) -> &[BitRow] {
+        &self.rows[..self.len]
+    }
+
+    fn push(&mut self, bit: bool) {
+        if !self.open {
+            if self.len == MAX_ROWS {
+                return;
+            }
+            self.len += 1;
+            self.open = true;
+        }
+        self.rows[self.len - 1].push(bit);
+    }
+
+    fn end_row(&mut self) {
+        self.open = false;
+    }
+}
+
+/// Slice a frame of pulses into bits.
+pub fn decode(coding: LineCoding, pulses: &[Pulse], timing: &Timing) -> BitRows {
+    let mut rows = BitRows::new();
+    match coding {
+        LineCoding::Pwm => decode_pwm(pulses, timing, &mut rows),
+        LineCoding::Ppm => decode_ppm(pulses, timing, &mut rows),
+        LineCoding::Manchester => decode_manchester(pulses, timing, &mut rows),
+        LineCoding::DifferentialManchester => decode_dmc(pulses, timing, &mut rows),
+        LineCoding::Nrz => decode_nrz(pulses, timing, &mut rows),
+        LineCoding::Pcm => decode_pcm(pulses, timing, &mut rows),
+    }
+    rows
+}
+
+fn decode_pwm(pulses: &[Pulse], timing: &Timing, rows: &mut BitRows) {
+    for pulse in pulses.iter() {
+        if pulse.level {
+            match timing.classify(pulse.duration_us) {
+                Some(Width::Short) => rows.push(true),
+                Some(Width::Long) => rows.push(false),
+                None => rows.end_row(),
+            }
+        } else if timing.is_gap(pulse) {
+            rows.end_row();
+        }
+    }
+}
+
+fn decode_ppm(pulses: &[Pulse], timing: &Timing, rows: &mut BitRows) {
+    for pulse in pulses.iter().filter(|p| !p.level) {
+        if timing.is_gap(pulse) {
+            rows.end_row();
+            continue;
+        }
+        match timing.classify(pulse.duration_us) {
+            Some(Width::Short) => rows.push(false),
+            Some(Width::Long) => rows.push(true),
+            None => rows.end_row(),
+        }
+    }
+}
+
+fn decode_manchester(pulses: &[Pulse], timing: &Timing, rows: &mut BitRows) {
+    // First half of the bit in progress, a bit is the level of its first half.
+    let mut first_half = Some(false);
+    for pulse in pulses.iter() {
+        if timing.is_gap(pulse) {
+            if first_half == Some(true) {
+                rows.push(true);
+            }
+            rows.end_row();
+            first_half = Some(false);
+            continue;
+        }
+        let halves = match timing.classify(pulse.duration_us) {
+            Some(Width::Short) => 1,
+            Some(Width::Long) => 2,
+            None => {
+                rows.end_row();
+                first_half = None;
+                continue;
+            }
+        };
+        for _ in 0..halves {
+            first_half = match first_half {
+                None => Some(pulse.level),
+                Some(first) if first != pulse.level => {
+                    rows.push(first);
+                    None
+                }
+                // No transition in the middle of the bit, resynchronize.
+                Some(_) => {
+                    rows.end_row();
+                    Some(pulse.level)
+                }
+            };
+        }
+    }
+    if first_half == Some(true) {
+        rows.push(true);
+    }
+}
+
+fn decode_dmc(pulses: &[Pulse], timing: &Timing, rows: &mut BitRows) {
+    let mut half_bit = false;
+    for pulse in pulses.iter() {
+        if timing.is_gap(pulse) {
+            rows.end_row();
+            half_bit = false;
+            continue;
+        }
+        match timing.classify(pulse.duration_us) {
+            Some(Width::Short) if half_bit => {
+                rows.push(false);
+                half_bit = false;
+            }
+            Some(Width::Short) => half_bit = true,
+            Some(Width::Long) if !half_bit => rows.push(true),
+            _ => {
+                rows.end_row();
+                half_bit = false;
+            }
+        }
+    }
+}
+
+fn decode_nrz(pulses: &[Pulse], timing: &Timing, rows: &mut BitRows) {
+    let symbol_us = timing.short_us.max(1);
+    for pulse in pulses.iter() {
+        if timing.is_gap(pulse) {
+            rows.end_row();
+            continue;
+        }
+        let bits = pulse.duration_us.saturating_add(symbol_us / 2) / symbol_us;
+        // A row can not hold more bits, a long pulse would only spin here.
+        for _ in 0..bits.min(MAX_ROW_BITS as u32) {
+            rows.push(pulse.level);
+        }
+    }
+}
+
+fn decode_pcm(pulses: &[Pulse], timing: &Timing, rows: &mut BitRows) {
+    let period_us = timing.long_us.max(1);
+    let mut high_us = None;
+    for pulse in pulses.iter() {
+        if pulse.level {
+            if pulse.duration_us.abs_diff(timing.short_us) <= timing.tolerance_us {
+                rows.push(true);
+                high_us = Some(pulse.duration_us);
+            } else {
+                rows.end_row();
+                high_us = None;
+            }
+        } else if timing.is_gap(pulse) {
+            rows.end_row();
+            high_us = None;
+        } else if let Some(high_us) = high_us.take() {
+            // Whole bit periods after the one with the pulse.
+            let periods =
+                high_us.saturating_add(pulse.duration_us).saturating_add(period_us / 2) / period_us;
+            for _ in 1..periods.min(MAX_ROW_BITS as u32 + 1) {
+                rows.push(false);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ook::*;
+
+    fn pulse(level: bool, duration_us: u32) -> Pulse {
+        Pulse {
+            level,
+            duration_us,
+        }
+    }
+
+    fn timing(short_us: u32, long_us: u32) -> Timing {
+        Timing {
+            short_us,
+            long_us,
+            gap_us: 5000,
+            tolerance_us: 100,
+        }
+    }
+
+    fn rows(rows: &BitRows) -> ([(usize, &[u8]); MAX_ROWS], usize) {
+        let mut result = [(0, &[][..]); MAX_ROWS];
+        for (i, row) in rows.rows().iter().enumerate() {
+            result[i] = (row.len(), row.bytes());
+        }
+        (result, rows.rows().len())
+    }
+
+    #[test]
+    fn test_pwm() {
+        // 1011, gap, 01
+        let pulses = [
+            pulse(true, 300),
+            pulse(false, 900),
+            pulse(true, 900),
+            pulse(false, 300),
+            pulse(true, 320),
+            pulse(false, 880),
+            pulse(true, 280),
+            pulse(false, 8000),
+            pulse(true, 900),
+            pulse(false, 300),
+            pulse(true, 300),
+            pulse(false, 8000),
+        ];
+        let decoded = decode(LineCoding::Pwm, &pulses, &timing(300, 900));
+        let (rows, len) = rows(&decoded);
+        assert_eq!(rows[..len], [(4, &[0b1011_0000][..]), (2, &[0b0100_0000][..])]);
+    }
+
+    #[test]
+    fn test_pwm_invalid_pulse() {
+        let pulses = [pulse(true, 300), pulse(false, 900), pulse(true, 600), pulse(false, 300)];
+        let decoded = decode(LineCoding::Pwm, &pulses, &timing(300, 900));
+        let (rows, len) = rows(&decoded);
+        assert_eq!(rows[..len], [(1, &[0b1000_0000][..])]);
+    }
+
+    #[test]
+    fn test_ppm() {
+        // 0110
+        let pulses = [
+            pulse(true, 500),
+            pulse(false, 1000),
+            pulse(true, 500),
+            pulse(false, 2000),
+            pulse(true, 500),
+            pulse(false, 2000),
+            pulse(true, 500),
+            pulse(false, 1000),
+            pulse(true, 500),
+            pulse(false, 9000),
+        ];
+        let decoded = decode(LineCoding::Ppm, &pulses, &timing(1000, 2000));
+        let (rows, len) = rows(&decoded);
+        assert_eq!(rows[..len], [(4, &[0b0110_0000][..])]);
+    }
+
+    #[test]
+    fn test_manchester() {
+        // 0 1 1 0 0 1 as halves LH HL HL LH LH HL, the first low half is not seen.
+        let pulses = [
+            pulse(true, 1000),
+            pulse(false, 500),
+            pulse(true, 500),
+            pulse(false, 1000),
+            pulse(true, 500),
+            pulse(false, 500),
+            pulse(true, 1000),
+            pulse(false, 9000),
+        ];
+        let decoded = decode(LineCoding::Manchester, &pulses, &timing(500, 1000));
+        let (rows, len) = rows(&decoded);
+        assert_eq!(rows[..len], [(6, &[0b0110_0100][..])]);
+    }
+
+    #[test]
+    fn test_differential_manchester() {
+        // 1 0 0 1 1 0
+        let pulses = [
+            pulse(true, 1000),
+            pulse(false, 500),
+            pulse(true, 500),
+            pulse(false, 500),
+            pulse(true, 500),
+            pulse(false, 1000),
+            pulse(true, 1000),
+            pulse(false, 500),
+            pulse(true, 500),
+            pulse(false, 9000),
+        ];
+        let decoded = decode(LineCoding::DifferentialManchester, &pulses, &timing(500, 1000));
+        let (rows, len) = rows(&decoded);
+        assert_eq!(rows[..len], [(6, &[0b1001_1000][..])]);
+    }
+
+    #[test]
+    fn test_nrz() {
+        // 1100 1011 1
+        let pulses = [
+            pulse(true, 410),
+            pulse(false, 390),
+            pulse(true, 200),
+            pulse(false, 200),
+            pulse(true, 600),
+            pulse(false, 9000),
+        ];
+        let decoded = decode(LineCoding::Nrz, &pulses, &timing(200, 200));
+        let (rows, len) = rows(&decoded);
+        assert_eq!(rows[..len], [(9, &[0b1100_1011, 0b1000_0000][..])]);
+    }
+
+    #[test]
+    fn test_pcm() {
+        // 1 0 1 1 0 0 1
+        let pulses = [
+            pulse(true, 200),
+            pulse(false, 1800),
+            pulse(true, 200),
+            pulse(false, 800),
+            pulse(true, 210),
+            pulse(false, 2790),
+            pulse(true, 190),
+            pulse(false, 9000),
+        ];
+        let decoded = decode(LineCoding::Pcm, &pulses, &timing(200, 1000));
+        let (rows, len) = rows(&decoded);
+        assert_eq!(rows[..len], [(7, &[0b1011_0010][..])]);
+    }
+
+    #[test]
+    fn test_long_pulses() {
+        let timing = Timing {
+            short_us: 2,
+            long_us: 2,
+            gap_us: 0,
+            tolerance_us: 0,
+        };
+        let decoded = decode(LineCoding::Nrz, &[pulse(true, u32::MAX)], &timing);
+        assert_eq!(decoded.rows()[0].len(), MAX_ROW_BITS);
+
+        let pulses = [pulse(true, 2), pulse(false, u32::MAX)];
+        let decoded = decode(LineCoding::Pcm, &pulses, &timing);
+        assert_eq!(decoded.rows()[0].len(), MAX_ROW_BITS);
+    }
+
+    #[test]
+    fn test_row_capacity() {
+        let mut pulses = [pulse(true, 300); 2 * (MAX_ROWS + 1)];
+        for gap in pulses.iter_mut().skip(1).step_by(2) {
+            *gap = pulse(false, 9000);
+        }
+        let decoded = decode(LineCoding::Pwm, &pulses, &timing(300, 900));
+        assert_eq!(decoded.rows().len(), MAX_ROWS);
+
+        let decoded = decode(LineCoding::Nrz, &[pulse(true, 1000)], &timing(1, 1));
+        assert_eq!(decoded.rows()[0].len(), MAX_ROW_BITS);
+        assert!(decoded.rows()[0].bit(MAX_ROW_BITS - 1));
+        assert!(!decoded.rows()[0].bit(MAX_ROW_BITS));
+    }
+
+    #[test]
+    fn test_estimate() {
+        let pulses = [
+            pulse(true, 290),
+            pulse(false, 910),
+            pulse(true, 890),
+            pulse(false, 310),
+            pulse(true, 310),
+            pulse(false, 8000),
+        ];
+        assert_eq!(
+            Timing::estimate(&pulses, Some(true), 5000),
+            Some(Timing {
+                short_us: 300,
+                long_us: 890,
+                gap_us: 5000,
+                tolerance_us: 295,
+            })
+        );
+        assert_eq!(
+            Timing::estimate(&pulses, None, 5000),
+            Some(Timing {
+                short_us: 303,
+                long_us: 900,
+                gap_us: 5000,
+                tolerance_us: 298,
+            })
+        );
+        assert_eq!(
+            Timing::estimate(&pulses[..1], Some(true), 5000),
+            Some(Timing {
+                short_us: 290,
+                long_us: 290,
+                gap_us: 5000,
+                tolerance_us: 72,
+            })
+        );
+        assert_eq!(Timing::estimate(&pulses, Some(false), 300), None);
+    }
+}
@@ -0,0 +1,31 @@
+//! On-off keying (OOK) signals in asynchronous serial mode.
+//!
+//! With [`DataMode::AsynchronousSerial`](crate::DataMode::AsynchronousSerial) the demodulated
+//! signal is output on GDO0/GDO2 in RX, without any packet handling. This is how most 433 MHz
+//! remotes and sensors are received, by timing the pulses on the pin. In TX the signal is
+//! sent by driving GDO0.
+//!
+//! Captured frames are sliced into bits with [`decode`], using one of the common line codings.
+
+mod capture;
+mod decode;
+mod transmit;
+
+pub use self::capture::*;
+pub use self::decode::*;
+pub use self::transmit::*;
+
+/// A period the signal stayed at one level.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Pulse {
+    /// Carrier on (high) or off (low).
+    pub level: bool,
+    /// Duration in microseconds.
+    pub duration_us: u32,
+}
+
+/// Microsecond time source.
+pub trait Clock {
+    /// Current time in microseconds, allowed to wrap around.
+    fn now_us(&mut self) -> u32;
+}
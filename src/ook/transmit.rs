@@ -0,0 +1,172 @@
+use hal::blocking::delay::DelayUs;
+use hal::digital::v2::OutputPin;
+
+use super::Pulse;
+
+/// Signal to send with [`Cc1101::transmit_ook`](crate::Cc1101::transmit_ook).
+#[derive(Clone, Copy, Debug)]
+pub enum Waveform<'a> {
+    /// Explicit pulses, a trailing low pulse gives the gap between repeats.
+    Pulses(&'a [Pulse]),
+    /// The first `len` bits of `data`, most significant bit first, each sending carrier on (1)
+    /// or off (0) for `symbol_us` microseconds.
+    Bits {
+        data: &'a [u8],
+        len: usize,
+        symbol_us: u32,
+    },
+}
+
+impl<'a> Waveform<'a> {
+    /// Drive `pin` with the waveform, leaving it low.
+    pub(crate) fn send<P, D>(&self, pin: &mut P, delay: &mut D) -> Result<(), P::Error>
+    where
+        P: OutputPin,
+        D: DelayUs<u32>,
+    {
+        match *self {
+            Waveform::Pulses(pulses) => {
+                for pulse in pulses.iter() {
+                    set_level(pin, pulse.level)?;
+                    delay.delay_us(pulse.duration_us);
+                }
+            }
+            Waveform::Bits {
+                data,
+                len,
+                symbol_us,
+            } => {
+                let len = len.min(data.len() * 8);
+                let bit = |i: usize| data[i / 8] & (0x80 >> (i % 8)) != 0;
+
+                // Send runs of equal bits as one pulse, to keep the delay overhead out of the
+                // symbol timing.
+                let mut i = 0;
+                while i < len {
+                    let level = bit(i);
+                    let mut run = 1;
+                    while i + run < len && bit(i + run) == level {
+                        run += 1;
+                    }
+                    set_level(pin, level)?;
+                    // Split runs longer than a single delay can take.
+                    let mut remaining_us = symbol_us as u64 * run as u64;
+                    while remaining_us > 0 {
+                        let us = remaining_us.min(u32::MAX as u64) as u32;
+                        delay.delay_us(us);
+                        remaining_us -= us as u64;
+                    }
+                    i += run;
+                }
+            }
+        }
+        pin.set_low()
+    }
+}
+
+fn set_level<P: OutputPin>(pin: &mut P, level: bool) -> Result<(), P::Error> {
+    if level {
+        pin.set_high()
+    } else {
+        pin.set_low()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ook::*;
+    use core::cell::{Cell, RefCell};
+    use core::convert::Infallible;
+    use hal::blocking::delay::DelayUs;
+    use hal::digital::v2::OutputPin;
+
+    struct Pin<'a>(&'a Cell<bool>);
+
+    impl<'a> OutputPin for Pin<'a> {
+        type Error = Infallible;
+
+        fn set_low(&mut self) -> Result<(), Infallible> {
+            self.0.set(false);
+            Ok(())
+        }
+
+        fn set_high(&mut self) -> Result<(), Infallible> {
+            self.0.set(true);
+            Ok(())
+        }
+    }
+
+    // Records the level of the pin during each delay.
+    struct Delay<'a> {
+        level: &'a Cell<bool>,
+        pulses: RefCell<([Pulse; 8], usize)>,
+    }
+
+    impl<'a> DelayUs<u32> for Delay<'a> {
+        fn delay_us(&mut self, us: u32) {
+            let (pulses, len) = &mut *self.pulses.borrow_mut();
+            pulses[*len] = Pulse {
+                level: self.level.get(),
+                duration_us: us,
+            };
+            *len += 1;
+        }
+    }
+
+    fn pulse(level: bool, duration_us: u32) -> Pulse {
+        Pulse {
+            level,
+            duration_us,
+        }
+    }
+
+    fn send(waveform: Waveform) -> ([Pulse; 8], usize, bool) {
+        let level = Cell::new(true);
+        let mut delay = Delay {
+            level: &level,
+            pulses: RefCell::new(([pulse(false, 0); 8], 0)),
+        };
+        waveform.send(&mut Pin(&level), &mut delay).unwrap();
+        let (pulses, len) = delay.pulses.into_inner();
+        (pulses, len, level.get())
+    }
+
+    #[test]
+    fn test_send_pulses() {
+        let pulses = [pulse(true, 300), pulse(false, 900), pulse(true, 900), pulse(false, 300)];
+        let (sent, len, level) = send(Waveform::Pulses(&pulses));
+        assert_eq!(sent[..len], pulses);
+        assert!(!level);
+    }
+
+    #[test]
+    fn test_send_bits() {
+        let (sent, len, level) = send(Waveform::Bits {
+            data: &[0b1011_0001, 0b1000_0000],
+            len: 10,
+            symbol_us: 100,
+        });
+        assert_eq!(
+            sent[..len],
+            [
+                pulse(true, 100),
+                pulse(false, 100),
+                pulse(true, 200),
+                pulse(false, 300),
+                pulse(true, 200),
+                pulse(false, 100),
+            ]
+        );
+        assert!(!level);
+    }
+
+    #[test]
+    fn test_send_long_run() {
+        let (sent, len, _) = send(Waveform::Bits {
+            data: &[0b1110_0000],
+            len: 3,
+            symbol_us: u32::MAX / 2,
+        });
+        assert_eq!(sent[..len], [pulse(true, u32::MAX), pulse(true, u32::MAX / 2 - 1)]);
+    }
+}
@@ -0,0 +1,104 @@
+use crate::lowlevel::types::*;
+
+/// Packet handler configuration.
+#[derive(Clone, Copy)]
+pub struct PacketHandling {
+    /// Minimum number of preamble bytes to be transmitted.
+    pub preamble: NumPreamble,
+    /// Preamble quality estimator threshold, 0 to 7. Sync words are only accepted when the
+    /// preamble quality indicator is at least `4 * threshold`, 0 accepts all sync words.
+    pub preamble_quality_threshold: u8,
+    /// CRC calculation in TX and CRC check in RX.
+    pub crc: bool,
+    /// Flush the RX FIFO when the CRC is not OK, requires `crc`.
+    pub crc_autoflush: bool,
+    /// Data whitening.
+    pub whitening: bool,
+    /// Forward Error Correction with interleaving, requires fixed packet length.
+    pub fec: bool,
+    /// Manchester encoding, not supported with 4-FSK and MSK.
+    pub manchester: bool,
+}
+
+impl Default for PacketHandling {
+    /// Reset values of the packet handler registers.
+    fn default() -> Self {
+        PacketHandling {
+            preamble: NumPreamble::N_4,
+            preamble_quality_threshold: 0,
+            crc: true,
+            crc_autoflush: false,
+            whitening: true,
+            fec: false,
+            manchester: false,
+        }
+    }
+}
+
+impl PacketHandling {
+    /// Whether the options can be combined, with the given MDMCFG2.MOD_FORMAT and
+    /// PKTCTRL0.LENGTH_CONFIG.
+    pub(crate) fn is_valid(&self, mod_format: ModFormat, length_config: LengthConfig) -> bool {
+        if self.preamble_quality_threshold > 7 {
+            return false;
+        }
+        if self.crc_autoflush && !self.crc {
+            return false;
+        }
+        if self.fec && length_config != LengthConfig::FIXED {
+            return false;
+        }
+        if self.manchester && !manchester_supported(mod_format) {
+            return false;
+        }
+        true
+    }
+}
+
+/// Manchester encoding is not supported with 4-FSK and MSK.
+pub(crate) fn manchester_supported(mod_format: ModFormat) -> bool {
+    mod_format != ModFormat::MOD_4FSK && mod_format != ModFormat::MOD_MSK
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::packet::*;
+
+    #[test]
+    fn test_packet_handling_validation() {
+        let fixed = LengthConfig::FIXED;
+        let variable = LengthConfig::VARIABLE;
+        let gfsk = ModFormat::MOD_GFSK;
+
+        assert!(PacketHandling::default().is_valid(gfsk, variable));
+
+        let fec = PacketHandling {
+            fec: true,
+            ..PacketHandling::default()
+        };
+        assert!(fec.is_valid(gfsk, fixed));
+        assert!(!fec.is_valid(gfsk, variable));
+
+        let manchester = PacketHandling {
+            manchester: true,
+            ..PacketHandling::default()
+        };
+        assert!(manchester.is_valid(gfsk, variable));
+        assert!(manchester.is_valid(ModFormat::MOD_ASK_OOK, variable));
+        assert!(!manchester.is_valid(ModFormat::MOD_4FSK, variable));
+        assert!(!manchester.is_valid(ModFormat::MOD_MSK, variable));
+
+        let autoflush = PacketHandling {
+            crc: false,
+            crc_autoflush: true,
+            ..PacketHandling::default()
+        };
+        assert!(!autoflush.is_valid(gfsk, variable));
+
+        let pqt = PacketHandling {
+            preamble_quality_threshold: 8,
+            ..PacketHandling::default()
+        };
+        assert!(!pqt.is_valid(gfsk, variable));
+    }
+}
@@ -1,11 +1,63 @@
-const RSSI_OFFSET: i16 = 74; // Table 31: Typical RSSI_offset Values
+/// Offset for the reset configuration of the chip, 115 kBaud at 800 MHz.
+pub const RSSI_OFFSET: i16 = 74; // Table 31: Typical RSSI_offset Values
 
-pub fn rssi_to_dbm(raw: u8) -> i16 {
+/// Table 31: Typical RSSI_offset Values, by data rate and frequency band.
+const RSSI_OFFSETS: [(u64, i16, i16); 4] = [
+    // (baud, 433 MHz, 868 MHz)
+    (1_200, 75, 74),
+    (38_400, 75, 74),
+    (250_000, 79, 78),
+    (500_000, 79, 77),
+];
+
+/// rssi_offset returns the typical offset for the given data rate and frequency.
+pub fn rssi_offset(baud: u64, hz: u64) -> i16 {
+    // The table only covers 433 and 868 MHz, 315 and 915 MHz use the closest band.
+    let low_band = hz < 600_000_000;
+
+    // Pick the row with the data rate closest to the configured one.
+    let mut best = RSSI_OFFSETS[0];
+    for row in RSSI_OFFSETS.iter() {
+        if row.0.abs_diff(baud) < best.0.abs_diff(baud) {
+            best = *row;
+        }
+    }
+
+    if low_band {
+        best.1
+    } else {
+        best.2
+    }
+}
+
+pub fn rssi_to_dbm(raw: u8, offset: i16) -> i16 {
     let rssi = raw as i16;
     // According to spec 17.3
     if rssi < 128 {
-        rssi / 2 - RSSI_OFFSET
+        rssi / 2 - offset
     } else {
-        (rssi - 256) / 2 - RSSI_OFFSET
+        (rssi - 256) / 2 - offset
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rssi::*;
+
+    #[test]
+    fn test_rssi_offset() {
+        assert_eq!(rssi_offset(1_200, 433_000_000), 75);
+        assert_eq!(rssi_offset(115_051, 868_000_000), 74);
+        assert_eq!(rssi_offset(249_938, 433_920_000), 79);
+        assert_eq!(rssi_offset(499_877, 915_000_000), 77);
+        assert_eq!(rssi_offset(4_800, 315_000_000), 75);
+    }
+
+    #[test]
+    fn test_rssi_to_dbm() {
+        assert_eq!(rssi_to_dbm(0x00, 74), -74);
+        assert_eq!(rssi_to_dbm(0x7F, 74), -11);
+        assert_eq!(rssi_to_dbm(0x80, 74), -138);
+        assert_eq!(rssi_to_dbm(0xFF, 79), -79);
     }
 }
@@ -0,0 +1,265 @@
+use hal::blocking::delay::DelayUs;
+use hal::blocking::spi::{Transfer, Write};
+use hal::digital::v2::OutputPin;
+
+use crate::lowlevel::registers::*;
+use crate::lowlevel::types::*;
+use crate::rssi::rssi_offset;
+use crate::{Cc1101, Error, RadioMode};
+
+/// Range of frequencies to sweep.
+#[derive(Clone, Copy, Debug)]
+pub enum ScanRange {
+    /// Sweep from `start` to `stop` Hz (inclusive) in steps of `step` Hz.
+    Frequency {
+        start: u64,
+        stop: u64,
+        step: u64,
+    },
+    /// Sweep channels `first` to `last` (inclusive) using the configured base frequency and
+    /// channel spacing.
+    Channel {
+        first: u8,
+        last: u8,
+    },
+}
+
+/// RSSI measured at one step of a scan.
+#[derive(Clone, Copy, Debug)]
+pub struct ScanSample {
+    /// Frequency in Hz.
+    pub frequency: u64,
+    /// Highest RSSI in dBm seen while dwelling on the frequency.
+    pub peak_dbm: i16,
+    /// Average RSSI in dBm while dwelling on the frequency.
+    pub average_dbm: i16,
+}
+
+/// Iterator sweeping a [`ScanRange`], created by [`Cc1101::scan`].
+pub struct Scanner<'a, SPI, CS, D> {
+    cc1101: &'a mut Cc1101<SPI, CS>,
+    delay: &'a mut D,
+    range: ScanRange,
+    step: u32,
+    samples: u8,
+    dwell_us: u32,
+    // Base frequency and channel spacing, only used for channel scans.
+    base: u64,
+    spacing: u64,
+    // Frequency in FREQ2/1/0 and the data rate, for the RSSI offset once the scan ends.
+    tuned: u64,
+    baud: u64,
+}
+
+impl<'a, SPI, CS, D, SpiE, GpioE> Scanner<'a, SPI, CS, D>
+where
+    SPI: Transfer<u8, Error = SpiE> + Write<u8, Error = SpiE>,
+    CS: OutputPin<Error = GpioE>,
+    D: DelayUs<u32>,
+{
+    pub(crate) fn new(
+        cc1101: &'a mut Cc1101<SPI, CS>,
+        delay: &'a mut D,
+        range: ScanRange,
+        bandwidth: u64,
+        samples: u8,
+    ) -> Result<Self, Error<SpiE, GpioE>> {
+        if matches!(
+            range,
+            ScanRange::Frequency {
+                step: 0,
+                ..
+            }
+        ) {
+            return Err(Error::InvalidArgument);
+        }
+
+        cc1101.set_chanbw(bandwidth)?;
+        cc1101.set_radio_mode(RadioMode::Idle)?;
+
+        let freq = cc1101.radio.read_composite::<FREQ>()?;
+        let drate = cc1101.radio.read_composite::<DRATE>()?;
+        let chanspc = cc1101.radio.read_composite::<CHANSPC>()?;
+
+        let agcctrl0 = cc1101.radio.read::<AGCCTRL0>()?;
+        let dwell_us = settle_time_us(
+            bandwidth,
+            agcctrl0.filter_length().map_or_else(|bits| bits, |length| length.value()),
+        );
+
+        Ok(Scanner {
+            cc1101,
+            delay,
+            range,
+            step: 0,
+            samples: samples.max(1),
+            dwell_us,
+            base: freq.hz(),
+            spacing: chanspc.hz(),
+            tuned: freq.hz(),
+            baud: drate.baud(),
+        })
+    }
+
+    /// Time spent waiting for RSSI to settle at each step, in microseconds.
+    pub fn dwell_us(&self) -> u32 {
+        self.dwell_us
+    }
+
+    fn tune(&mut self, frequency: u64) -> Result<(), Error<SpiE, GpioE>> {
+        // One burst, so the synthesizer never sees a mix of the old and new frequency words.
+        let freq = FREQ::from_hz(frequency);
+        self.cc1101.radio.write_composite(&freq)?;
+        self.tuned = freq.hz();
+        Ok(())
+    }
+
+    fn measure(&mut self, frequency: u64) -> Result<ScanSample, Error<SpiE, GpioE>> {
+        // Auto-calibration (MCSM0.FS_AUTOCAL) happens on the way from IDLE to RX.
+        self.cc1101.radio.write_strobe(Command::SRX)?;
+        self.cc1101.await_machine_state(MachineState::RX)?;
+
+        let mut peak = i16::MIN;
+        let mut sum = 0i32;
+        for _ in 0..self.samples {
+            self.delay.delay_us(self.dwell_us);
+            let dbm = self.cc1101.get_rssi_dbm()?;
+            peak = peak.max(dbm);
+            sum += dbm as i32;
+        }
+
+        self.cc1101.set_radio_mode(RadioMode::Idle)?;
+
+        Ok(ScanSample {
+            frequency,
+            peak_dbm: peak,
+            average_dbm: (sum / self.samples as i32) as i16,
+        })
+    }
+}
+
+impl<'a, SPI, CS, D, SpiE, GpioE> Iterator for Scanner<'a, SPI, CS, D>
+where
+    SPI: Transfer<u8, Error = SpiE> + Write<u8, Error = SpiE>,
+    CS: OutputPin<Error = GpioE>,
+    D: DelayUs<u32>,
+{
+    type Item = Result<ScanSample, Error<SpiE, GpioE>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let frequency = match self.range {
+            ScanRange::Frequency {
+                start,
+                stop,
+                step,
+            } => {
+                let frequency =
+                    step.checked_mul(self.step as u64).and_then(|offset| start.checked_add(offset));
+                let frequency = match frequency {
+                    Some(frequency) if frequency <= stop => frequency,
+                    _ => return None,
+                };
+                if let Err(e) = self.tune(frequency) {
+                    return Some(Err(e));
+                }
+                frequency
+            }
+            ScanRange::Channel {
+                first,
+                last,
+            } => {
+                let channel = first as u32 + self.step;
+                if channel > last as u32 {
+                    return None;
+                }
+                if let Err(e) = self.cc1101.radio.write_register(Config::CHANNR, channel as u8) {
+                    return Some(Err(e.into()));
+                }
+                self.base + self.spacing * channel as u64
+            }
+        };
+        self.step += 1;
+        // The offset depends on the band, which a sweep may cross.
+        self.cc1101.rssi_offset = rssi_offset(self.baud, frequency);
+        Some(self.measure(frequency))
+    }
+}
+
+impl<'a, SPI, CS, D> Drop for Scanner<'a, SPI, CS, D> {
+    fn drop(&mut self) {
+        // Back to the offset for the configured frequency, as `Cc1101::update_rssi_offset`
+        // would read it from FREQ2/1/0.
+        self.cc1101.rssi_offset = rssi_offset(self.baud, self.tuned);
+    }
+}
+
+/// Time for RSSI to settle after entering RX, in microseconds.
+///
+/// RSSI is updated every `8 * 2^FILTER_LENGTH / (2 * BW)` seconds (datasheet section 17.3),
+/// wait for a few updates so the value reflects the new channel.
+fn settle_time_us(bandwidth: u64, filter_length: u8) -> u32 {
+    const UPDATES: u64 = 3;
+    let update_us = (8u64 << filter_length) * 1_000_000 / (2 * bandwidth.max(1));
+    (UPDATES * update_us).min(u32::MAX as u64) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lowlevel::sim::{self, Chip, Delay};
+    use crate::*;
+    use core::cell::RefCell;
+
+    #[test]
+    fn test_scan_bandwidth() {
+        let chip = RefCell::new(Chip::new());
+        let mut radio = sim::cc1101(&chip);
+        let range = ScanRange::Channel {
+            first: 0,
+            last: 1,
+        };
+        for &bandwidth in [0, 58_034, 812_501, u64::MAX].iter() {
+            let scan = radio.scan(range, bandwidth, 1, &mut Delay).map(|_| ());
+            assert!(matches!(scan, Err(Error::InvalidArgument)));
+        }
+        for &bandwidth in [58_035, 812_500].iter() {
+            assert_eq!(radio.scan(range, bandwidth, 1, &mut Delay).unwrap().count(), 2);
+        }
+    }
+
+    #[test]
+    fn test_scan_overflow() {
+        let chip = RefCell::new(Chip::new());
+        let mut radio = sim::cc1101(&chip);
+        let range = ScanRange::Frequency {
+            start: 433_000_000,
+            stop: u64::MAX,
+            step: u64::MAX - 1_000_000,
+        };
+        let mut delay = Delay;
+        let mut scan = radio.scan(range, 812_500, 1, &mut delay).unwrap();
+        assert_eq!(scan.next().unwrap().unwrap().frequency, 433_000_000);
+        assert!(scan.next().is_none());
+    }
+
+    #[test]
+    fn test_scan_rssi_offset() {
+        let chip = RefCell::new(Chip::new());
+        let mut radio = sim::cc1101(&chip);
+        // 250 kBaud, with an offset of 79 dB at 433 MHz and 78 dB at 868 MHz.
+        radio.set_data_rate(250_000).unwrap();
+        let range = ScanRange::Frequency {
+            start: 590_000_000,
+            stop: 610_000_000,
+            step: 20_000_000,
+        };
+        let mut delay = Delay;
+        let mut scan = radio.scan(range, 812_500, 1, &mut delay).unwrap();
+        assert_eq!(scan.next().unwrap().unwrap().peak_dbm, -79);
+        assert_eq!(scan.next().unwrap().unwrap().peak_dbm, -78);
+        assert!(scan.next().is_none());
+        drop(scan);
+
+        // Still tuned above 600 MHz.
+        assert_eq!(radio.get_rssi_dbm().unwrap(), -78);
+    }
+}
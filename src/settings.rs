@@ -0,0 +1,184 @@
+use crate::lowlevel::registers::Config;
+
+/// Values for a set of configuration registers, and optionally the PA table.
+///
+/// Registers without a value are left as they are when the settings are applied with
+/// [`Cc1101::apply_settings`](crate::Cc1101::apply_settings), or with
+/// [`Cc1101::update_settings`](crate::Cc1101::update_settings) to write only what changed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RegisterSettings {
+    values: [Option<u8>; Config::ALL.len()],
+    pa_table: Option<[u8; 8]>,
+}
+
+impl Default for RegisterSettings {
+    fn default() -> Self {
+        RegisterSettings::new()
+    }
+}
+
+impl RegisterSettings {
+    /// Settings without any register values.
+    pub const fn new() -> Self {
+        RegisterSettings {
+            values: [None; Config::ALL.len()],
+            pa_table: None,
+        }
+    }
+
+    /// Set the value of a register.
+    pub const fn with(mut self, reg: Config, value: u8) -> Self {
+        self.values[reg as usize] = Some(value);
+        self
+    }
+
+    /// Set the PA table.
+    pub const fn with_pa_table(mut self, pa_table: [u8; 8]) -> Self {
+        self.pa_table = Some(pa_table);
+        self
+    }
+
+    /// Set the value of a register.
+    pub fn set(&mut self, reg: Config, value: u8) {
+        self.values[reg.addr() as usize] = Some(value);
+    }
+
+    /// The value of a register, if set.
+    pub const fn get(&self, reg: Config) -> Option<u8> {
+        self.values[reg as usize]
+    }
+
+    /// Set the PA table.
+    pub fn set_pa_table(&mut self, pa_table: [u8; 8]) {
+        self.pa_table = Some(pa_table);
+    }
+
+    /// The PA table, if set.
+    pub const fn pa_table(&self) -> Option<[u8; 8]> {
+        self.pa_table
+    }
+
+    /// The registers with a value, in address order.
+    pub fn iter(&self) -> impl Iterator<Item = (Config, u8)> + '_ {
+        Config::ALL
+            .iter()
+            .zip(self.values.iter())
+            .filter_map(|(&reg, value)| Some((reg, (*value)?)))
+    }
+
+    /// The values in `target` that are different here, or not set here.
+    pub fn diff(&self, target: &RegisterSettings) -> RegisterSettings {
+        let mut diff = RegisterSettings::new();
+        for (reg, value) in target.iter() {
+            if self.get(reg) != Some(value) {
+                diff.set(reg, value);
+            }
+        }
+        if target.pa_table.is_some() && target.pa_table != self.pa_table {
+            diff.pa_table = target.pa_table;
+        }
+        diff
+    }
+
+    /// Fill gaps of a single register between registers with a value, with the value in
+    /// `current`. Writing the register costs the same as starting a new burst.
+    pub(crate) fn fill_gaps(&mut self, current: &RegisterSettings) {
+        for addr in 1..self.values.len() - 1 {
+            if self.values[addr].is_none()
+                && self.values[addr - 1].is_some()
+                && self.values[addr + 1].is_some()
+            {
+                self.values[addr] = current.values[addr];
+            }
+        }
+    }
+
+    /// Runs of consecutive registers with a value, as the first register and the number of
+    /// registers.
+    pub(crate) fn runs(&self) -> impl Iterator<Item = (Config, usize)> + '_ {
+        let mut addr = 0;
+        core::iter::from_fn(move || {
+            while addr < self.values.len() && self.values[addr].is_none() {
+                addr += 1;
+            }
+            let start = addr;
+            while addr < self.values.len() && self.values[addr].is_some() {
+                addr += 1;
+            }
+            (addr > start).then(|| (Config::ALL[start], addr - start))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lowlevel::registers::*;
+    use crate::settings::*;
+
+    #[test]
+    fn test_register_settings() {
+        const SETTINGS: RegisterSettings =
+            RegisterSettings::new().with(Config::MDMCFG2, 0x30).with(Config::IOCFG0, 0x0D);
+
+        let mut settings = SETTINGS.clone();
+        settings.set(Config::TEST0, 0x09);
+        assert_eq!(settings.get(Config::IOCFG0), Some(0x0D));
+        assert_eq!(settings.get(Config::IOCFG2), None);
+
+        let mut iter = settings.iter().map(|(reg, value)| (reg.addr(), value));
+        assert_eq!(iter.next(), Some((0x02, 0x0D)));
+        assert_eq!(iter.next(), Some((0x12, 0x30)));
+        assert_eq!(iter.next(), Some((0x2E, 0x09)));
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn test_diff() {
+        let current = RegisterSettings::new()
+            .with(Config::FREQ2, 0x10)
+            .with(Config::FREQ1, 0xA7)
+            .with(Config::MDMCFG2, 0x30)
+            .with_pa_table([0x00, 0xC0, 0, 0, 0, 0, 0, 0]);
+        let target = RegisterSettings::new()
+            .with(Config::FREQ2, 0x10)
+            .with(Config::FREQ1, 0xB0)
+            .with(Config::FREQ0, 0x71)
+            .with(Config::MDMCFG2, 0x30)
+            .with_pa_table([0x00, 0xC0, 0, 0, 0, 0, 0, 0]);
+
+        let diff = current.diff(&target);
+        let mut iter = diff.iter().map(|(reg, value)| (reg.addr(), value));
+        assert_eq!(iter.next(), Some((0x0E, 0xB0)));
+        assert_eq!(iter.next(), Some((0x0F, 0x71)));
+        assert_eq!(iter.next(), None);
+        assert_eq!(diff.pa_table(), None);
+
+        assert_eq!(
+            target.diff(&RegisterSettings::new().with_pa_table([0xC0; 8])).pa_table(),
+            Some([0xC0; 8])
+        );
+    }
+
+    #[test]
+    fn test_runs() {
+        let current = RegisterSettings::new().with(Config::MDMCFG3, 0x83);
+        let mut settings = RegisterSettings::new()
+            .with(Config::IOCFG2, 0x0D)
+            .with(Config::FREQ1, 0xB0)
+            .with(Config::FREQ0, 0x71)
+            .with(Config::MDMCFG4, 0xF5)
+            .with(Config::MDMCFG2, 0x30)
+            .with(Config::DEVIATN, 0x15)
+            .with(Config::TEST0, 0x09);
+
+        // MDMCFG0 and MCSM2 are not known, MDMCFG3 is.
+        settings.fill_gaps(&current);
+        let mut runs = settings.runs().map(|(reg, len)| (reg.addr(), len));
+        assert_eq!(runs.next(), Some((0x00, 1)));
+        assert_eq!(runs.next(), Some((0x0E, 5)));
+        assert_eq!(runs.next(), Some((0x15, 1)));
+        assert_eq!(runs.next(), Some((0x2E, 1)));
+        assert_eq!(runs.next(), None);
+        assert_eq!(settings.get(Config::MDMCFG3), Some(0x83));
+    }
+}
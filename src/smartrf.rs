@@ -0,0 +1,337 @@
+//! TI SmartRF Studio register exports.
+//!
+//! Both the C header export (`#define SMARTRF_SETTING_FREQ2 0x10`) and register table exports
+//! (`FREQ2 0x10 Frequency Control Word, High Byte`, optionally preceded by the address, or as
+//! `{CC1101_FREQ2, 0x10},` array entries) are accepted. Lines that do not look like register
+//! settings, such as comments, headings and the summary (`Deviation = 20.629883`), are skipped;
+//! table lines are only taken for CC1101 register names. The result is applied with
+//! [`Cc1101::apply_settings`](crate::Cc1101::apply_settings).
+//!
+//! [`parse`] is a `const fn`, so an export can be embedded at compile time:
+//!
+//! ```
+//! use cc1101::smartrf;
+//! use cc1101::RegisterSettings;
+//!
+//! const SETTINGS: RegisterSettings = match smartrf::parse(
+//!     "#define SMARTRF_SETTING_FREQ2 0x10
+//!      #define SMARTRF_SETTING_FREQ1 0xB0
+//!      #define SMARTRF_SETTING_FREQ0 0x71",
+//! ) {
+//!     Ok(settings) => settings,
+//!     Err(_) => panic!("invalid SmartRF export"),
+//! };
+//! ```
+
+use crate::lowlevel::registers::{bytes_eq, Config, Status};
+use crate::RegisterSettings;
+
+const SETTING_PREFIX: &[u8] = b"SMARTRF_SETTING_";
+const CHIP_PREFIX: &[u8] = b"CC1101_";
+const PA_TABLE_PREFIX: &[u8] = b"PA_TABLE";
+
+/// SmartRF export parse errors, with the line number counting from 1.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Error {
+    /// The name is not a CC1101 register.
+    UnknownRegister(usize),
+    /// The register is a read-only status register.
+    ReadOnlyRegister(usize),
+    /// The value is missing, or not a byte in hexadecimal or decimal.
+    InvalidValue(usize),
+}
+
+/// Parse a SmartRF Studio register export.
+pub const fn parse(text: &str) -> Result<RegisterSettings, Error> {
+    let text = text.as_bytes();
+    let mut settings = RegisterSettings::new();
+    let mut pa_table = None;
+
+    let mut line = 0;
+    let mut start = 0;
+    while start < text.len() {
+        let mut end = start;
+        while end < text.len() && text[end] != b'\n' {
+            end += 1;
+        }
+        line += 1;
+
+        if let Some((name, value)) = setting(text, start, end) {
+            let value = match parse_value(value) {
+                Some(value) => value,
+                None => return Err(Error::InvalidValue(line)),
+            };
+            let name = strip_prefix(name, CHIP_PREFIX);
+
+            if let Some(reg) = config_register(name) {
+                settings = settings.with(reg, value);
+            } else if let Some(index) = pa_table_index(name) {
+                let mut table = match pa_table {
+                    Some(table) => table,
+                    None => [0u8; 8],
+                };
+                table[index] = value;
+                pa_table = Some(table);
+            } else if status_register(name) {
+                return Err(Error::ReadOnlyRegister(line));
+            } else {
+                return Err(Error::UnknownRegister(line));
+            }
+        }
+        start = end + 1;
+    }
+
+    if let Some(table) = pa_table {
+        settings = settings.with_pa_table(table);
+    }
+    Ok(settings)
+}
+
+/// Name and value of a register setting on the line from `start` to `end`.
+const fn setting(text: &[u8], start: usize, end: usize) -> Option<(&[u8], &[u8])> {
+    let mut pos = start;
+    while pos < end && text[pos].is_ascii_whitespace() {
+        pos += 1;
+    }
+    if pos < end && (text[pos] == b'/' || text[pos] == b'*') {
+        return None;
+    }
+
+    let first = next_word(text, start, end);
+    let second = next_word(text, first.1, end);
+    let third = next_word(text, second.1, end);
+
+    let first_word = slice(text, first);
+    if bytes_eq(first_word, b"define") {
+        // Other defines, such as SMARTRF_RADIO_CC1101, are not register settings.
+        let name = slice(text, second);
+        if name.len() > SETTING_PREFIX.len() && starts_with(name, SETTING_PREFIX) {
+            return Some((strip_prefix(name, SETTING_PREFIX), slice(text, third)));
+        }
+        return None;
+    }
+
+    // Table exports can have the register address before or after the name.
+    let (name, value) = if is_number(first_word) {
+        (slice(text, second), slice(text, third))
+    } else if is_number(slice(text, second)) && is_number(slice(text, third)) {
+        (first_word, slice(text, third))
+    } else {
+        (first_word, slice(text, second))
+    };
+    if name.is_empty() || is_number(name) || !is_number(value) {
+        return None;
+    }
+    // Summary lines, such as `Deviation 20 kHz`, have the same shape.
+    if !is_register_name(strip_prefix(name, CHIP_PREFIX)) {
+        return None;
+    }
+    Some((name, value))
+}
+
+const fn is_register_name(name: &[u8]) -> bool {
+    config_register(name).is_some() || pa_table_index(name).is_some() || status_register(name)
+}
+
+const fn config_register(name: &[u8]) -> Option<Config> {
+    match core::str::from_utf8(name) {
+        Ok(name) => Config::from_name(name),
+        Err(_) => None,
+    }
+}
+
+const fn status_register(name: &[u8]) -> bool {
+    match core::str::from_utf8(name) {
+        Ok(name) => Status::from_name(name).is_some(),
+        Err(_) => false,
+    }
+}
+
+/// Index of PA_TABLE0 to PA_TABLE7.
+const fn pa_table_index(name: &[u8]) -> Option<usize> {
+    if name.len() == PA_TABLE_PREFIX.len() + 1
+        && starts_with(name, PA_TABLE_PREFIX)
+        && name[name.len() - 1] >= b'0'
+        && name[name.len() - 1] <= b'7'
+    {
+        Some((name[name.len() - 1] - b'0') as usize)
+    } else {
+        None
+    }
+}
+
+/// Start and end of the next run of alphanumeric characters and underscores.
+const fn next_word(text: &[u8], mut pos: usize, end: usize) -> (usize, usize) {
+    while pos < end && !is_word_byte(text[pos]) {
+        pos += 1;
+    }
+    let start = pos;
+    while pos < end && is_word_byte(text[pos]) {
+        pos += 1;
+    }
+    (start, pos)
+}
+
+const fn is_word_byte(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte == b'_'
+}
+
+const fn is_number(word: &[u8]) -> bool {
+    !word.is_empty() && word[0].is_ascii_digit()
+}
+
+const fn slice(text: &[u8], (start, end): (usize, usize)) -> &[u8] {
+    text.split_at(end).0.split_at(start).1
+}
+
+const fn starts_with(word: &[u8], prefix: &[u8]) -> bool {
+    word.len() >= prefix.len() && bytes_eq(word.split_at(prefix.len()).0, prefix)
+}
+
+const fn strip_prefix<'a>(word: &'a [u8], prefix: &[u8]) -> &'a [u8] {
+    if starts_with(word, prefix) {
+        word.split_at(prefix.len()).1
+    } else {
+        word
+    }
+}
+
+/// A byte in hexadecimal with `0x` prefix, or decimal.
+const fn parse_value(word: &[u8]) -> Option<u8> {
+    let (digits, radix) =
+        if word.len() > 2 && word[0] == b'0' && (word[1] == b'x' || word[1] == b'X') {
+            (word.split_at(2).1, 16)
+        } else {
+            (word, 10)
+        };
+    if digits.is_empty() {
+        return None;
+    }
+
+    let mut value: u32 = 0;
+    let mut i = 0;
+    while i < digits.len() {
+        let digit = match digits[i] {
+            b'0'..=b'9' => digits[i] - b'0',
+            b'a'..=b'f' => digits[i] - b'a' + 10,
+            b'A'..=b'F' => digits[i] - b'A' + 10,
+            _ => return None,
+        } as u32;
+        if digit >= radix {
+            return None;
+        }
+        value = value * radix + digit;
+        if value > 0xFF {
+            return None;
+        }
+        i += 1;
+    }
+    Some(value as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lowlevel::registers::*;
+    use crate::smartrf::*;
+
+    const HEADER: &str = "/* Address Config = No address check */
+/* Base Frequency = 433.919830 */
+/* Deviation = 20.629883 */
+#ifndef SMARTRF_CC1101_H
+#define SMARTRF_CC1101_H
+
+#define SMARTRF_RADIO_CC1101
+#define SMARTRF_SETTING_IOCFG0           0x06
+#define SMARTRF_SETTING_FIFOTHR          0x47
+#define SMARTRF_SETTING_PKTCTRL0         0x05
+#define SMARTRF_SETTING_FREQ2            0x10
+#define SMARTRF_SETTING_FREQ1            0xB0
+#define SMARTRF_SETTING_FREQ0            0x71
+#define SMARTRF_SETTING_TEST0            0x09
+
+#endif
+";
+
+    #[test]
+    fn test_parse_header() {
+        let settings = parse(HEADER).unwrap();
+        assert_eq!(settings.iter().count(), 7);
+        assert_eq!(settings.get(Config::IOCFG0), Some(0x06));
+        assert_eq!(settings.get(Config::FREQ2), Some(0x10));
+        assert_eq!(settings.get(Config::TEST0), Some(0x09));
+        assert_eq!(settings.pa_table(), None);
+    }
+
+    #[test]
+    fn test_parse_table() {
+        let table = "Name      Address  Value  Description
+IOCFG2    0x0000   0x29   GDO2 Output Pin Configuration
+0x0012 MDMCFG2 0x30 Modem Configuration
+{CC1101_FSCTRL1, 0x06},
+MCSM0 = 24
+PA_TABLE1 0xC0
+";
+        let settings = parse(table).unwrap();
+        assert_eq!(settings.get(Config::IOCFG2), Some(0x29));
+        assert_eq!(settings.get(Config::MDMCFG2), Some(0x30));
+        assert_eq!(settings.get(Config::FSCTRL1), Some(0x06));
+        assert_eq!(settings.get(Config::MCSM0), Some(0x18));
+        assert_eq!(settings.pa_table(), Some([0x00, 0xC0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]));
+    }
+
+    #[test]
+    fn test_parse_table_export() {
+        // Register table export, with the summary of the settings before the registers.
+        let export = "Address Config = No address check
+Base Frequency = 433.919830
+CRC Enable = true
+Carrier Frequency = 433.919830
+Channel Number = 0
+Channel Spacing = 199.951172
+Data Rate = 1.19948
+Deviation = 5.157471
+Device Address = 0
+Modulation Format = 2-FSK
+Packet Length = 255
+Preamble Count = 4
+RX Filter BW = 58.035714
+TX Power = 10
+Deviation 20 kHz
+Modulation 2 FSK
+
+IOCFG0     0x0002  0x06  GDO0 Output Pin Configuration
+FIFOTHR    0x0003  0x47  RX FIFO and TX FIFO Thresholds
+PKTCTRL0   0x0008  0x05  Packet Automation Control
+FREQ2      0x000D  0x10  Frequency Control Word, High Byte
+FREQ1      0x000E  0xB0  Frequency Control Word, Middle Byte
+FREQ0      0x000F  0x71  Frequency Control Word, Low Byte
+MDMCFG4    0x0010  0xF5  Modem Configuration
+MDMCFG3    0x0011  0x83  Modem Configuration
+DEVIATN    0x0015  0x15  Modem Deviation Setting
+TEST0      0x002E  0x09  Various Test Settings
+";
+        let settings = parse(export).unwrap();
+        assert_eq!(settings.iter().count(), 10);
+        assert_eq!(settings.get(Config::FREQ2), Some(0x10));
+        assert_eq!(settings.get(Config::DEVIATN), Some(0x15));
+        assert_eq!(settings.get(Config::TEST0), Some(0x09));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(parse("#define SMARTRF_SETTING_FOO 0x01"), Err(Error::UnknownRegister(1)));
+        assert_eq!(parse("\nMARCSTATE 0x01"), Err(Error::ReadOnlyRegister(2)));
+        assert_eq!(parse("#define SMARTRF_SETTING_FREQ2 0x100"), Err(Error::InvalidValue(1)));
+        assert_eq!(parse("#define SMARTRF_SETTING_FREQ2"), Err(Error::InvalidValue(1)));
+        assert_eq!(parse("FREQ2 0xG1"), Err(Error::InvalidValue(1)));
+    }
+
+    #[test]
+    fn test_parse_const() {
+        const SETTINGS: RegisterSettings = match parse(HEADER) {
+            Ok(settings) => settings,
+            Err(_) => panic!("invalid SmartRF export"),
+        };
+        assert_eq!(SETTINGS, parse(HEADER).unwrap());
+    }
+}